@@ -75,7 +75,7 @@ function App() {
   const [gramsWanted, setGramsWanted] = useState<string>("1.000");
   const [calcGross, setCalcGross] = useState<bigint | null>(null);
   const [calcPreview, setCalcPreview] = useState<null | {
-    gbt_out_gm: bigint; fee_stable_1e7: bigint; net_stable_1e7: bigint; price_1e7: bigint; meets_min: boolean; capacity_left_gm: bigint;
+    gbt_out_gm: bigint; fee_stable_1e7: bigint; net_stable_1e7: bigint; price_1e7: bigint; fx_1e7: bigint; meets_min: boolean; capacity_left_gm: bigint;
   }>(null);
 
   // Deposit
@@ -155,6 +155,7 @@ function App() {
         networkPassphrase,
         minterId: MINTER,
         payer: wallet,
+        payTokenId: STABLE,
         amountStable1e7: scaled,     // <= ya en 1e7
         maxMineIdInclusive: MAX_MINE_ID,
       });
@@ -179,7 +180,7 @@ function App() {
     try {
       const trialAmt = 10_000_000n; // 1 USD
       const trial = await previewMintRPC({
-        rpcUrl, networkPassphrase, minterId: MINTER,
+        rpcUrl, networkPassphrase, minterId: MINTER, payTokenId: STABLE,
         amountStable1e7: trialAmt, maxIdInclusive: MAX_MINE_ID, source: ADMIN,
       });
       const price = trial.price_1e7;
@@ -196,7 +197,7 @@ function App() {
       const gross = ((neededNet * BigInt(BPS)) + BigInt(BPS - feeBps - 1)) / BigInt(BPS - feeBps);
 
       const prev = await previewMintRPC({
-        rpcUrl, networkPassphrase, minterId: MINTER,
+        rpcUrl, networkPassphrase, minterId: MINTER, payTokenId: STABLE,
         amountStable1e7: gross, maxIdInclusive: MAX_MINE_ID, source: ADMIN,
       });
 
@@ -221,6 +222,7 @@ function App() {
         networkPassphrase,
         minterId: MINTER,
         payer: wallet,
+        payTokenId: STABLE,
         amountStable1e7: calcGross,  // ya en 1e7
         maxMineIdInclusive: MAX_MINE_ID,
       });
@@ -163,6 +163,7 @@ export async function previewMintRPC(params: {
   rpcUrl: string;
   networkPassphrase: string;
   minterId: string;
+  payTokenId: string;
   amountStable1e7: bigint;
   maxIdInclusive: number;
   source: string;
@@ -171,6 +172,7 @@ export async function previewMintRPC(params: {
   net_stable_1e7: bigint;
   fee_stable_1e7: bigint;
   price_1e7: bigint;
+  fx_1e7: bigint;
   meets_min: boolean;
   capacity_left_gm: bigint;
 }> {
@@ -180,6 +182,7 @@ export async function previewMintRPC(params: {
     contractId: params.minterId,
     fn: "preview_mint",
     args: [
+      nativeToScVal(scAddr(params.payTokenId), { type: "address" }),
       nativeToScVal(params.amountStable1e7, { type: "i128" }),
       nativeToScVal(params.maxIdInclusive, { type: "u32" }),
     ],
@@ -188,9 +191,10 @@ export async function previewMintRPC(params: {
   // scValToNative ya devuelve BigInt/bool en los campos
   return {
     gbt_out_gm: BigInt(res.gbt_out_gm),
-    net_stable_1e7: BigInt(res.net_stable_1e7),
-    fee_stable_1e7: BigInt(res.fee_stable_1e7),
+    net_stable_1e7: BigInt(res.net_stable),
+    fee_stable_1e7: BigInt(res.fee_stable),
     price_1e7: BigInt(res.price_1e7),
+    fx_1e7: BigInt(res.fx_1e7),
     meets_min: !!res.meets_min,
     capacity_left_gm: BigInt(res.capacity_left_gm),
   };
@@ -203,6 +207,7 @@ export async function mintGBTWithFreighter(params: {
   networkPassphrase: string;
   minterId: string;
   payer: string;                // G...
+  payTokenId: string;           // C... token de pago aceptado por el minter
   amountStable1e7: bigint;      // en 1e7 (USD)
   maxMineIdInclusive: number;   // u32
 }): Promise<string> {
@@ -215,6 +220,7 @@ export async function mintGBTWithFreighter(params: {
     fn: "mint",
     args: [
       nativeToScVal(scAddr(params.payer), { type: "address" }),
+      nativeToScVal(scAddr(params.payTokenId), { type: "address" }),
       nativeToScVal(params.amountStable1e7, { type: "i128" }),
       nativeToScVal(params.maxMineIdInclusive, { type: "u32" }),
     ],
@@ -0,0 +1,123 @@
+#![no_std]
+
+//! Aritmética fixed-point compartida por los contratos de Alternun.
+//!
+//! Todas las operaciones son `checked`: devuelven `None` ante división por cero o
+//! si el resultado no cabe en `i128`. Los productos intermedios se calculan en
+//! 256 bits, así que `a * b / d` no desborda mientras el resultado final quepa.
+
+/// 100% en basis points.
+pub const BPS: i128 = 10_000;
+
+/// Mayor exponente con `10^n` representable en `i128`.
+pub const MAX_POW10: u32 = 38;
+
+/// Sentido del redondeo cuando la división no es exacta.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    /// Hacia -infinito.
+    Floor,
+    /// Hacia +infinito.
+    Ceil,
+}
+
+/// `a * b / d` con producto intermedio de 256 bits y el redondeo indicado.
+pub fn mul_div(a: i128, b: i128, d: i128, rounding: Rounding) -> Option<i128> {
+    if d == 0 {
+        return None;
+    }
+    let negative = (a < 0) ^ (b < 0) ^ (d < 0);
+    let (hi, lo) = mul_u128(a.unsigned_abs(), b.unsigned_abs());
+    let (q_hi, q_lo, rem) = div_u256(hi, lo, d.unsigned_abs());
+    if q_hi != 0 {
+        return None;
+    }
+
+    // |a*b/d| = q_lo + rem/|d|; se ajusta la magnitud según signo y redondeo.
+    let inexact = rem != 0;
+    let bump = match rounding {
+        Rounding::Floor => negative && inexact,
+        Rounding::Ceil => !negative && inexact,
+    };
+    let mag = if bump { q_lo.checked_add(1)? } else { q_lo };
+
+    if negative {
+        if mag > i128::MIN.unsigned_abs() {
+            None
+        } else {
+            Some((mag as i128).wrapping_neg())
+        }
+    } else {
+        i128::try_from(mag).ok()
+    }
+}
+
+/// `a / d` con el redondeo indicado.
+pub fn div(a: i128, d: i128, rounding: Rounding) -> Option<i128> {
+    mul_div(a, 1, d, rounding)
+}
+
+/// `amount * bps / 10_000`.
+pub fn apply_bps(amount: i128, bps: i128, rounding: Rounding) -> Option<i128> {
+    mul_div(amount, bps, BPS, rounding)
+}
+
+/// `10^n`, o `None` si no cabe en `i128`.
+pub fn pow10(n: u32) -> Option<i128> {
+    if n > MAX_POW10 {
+        return None;
+    }
+    10i128.checked_pow(n)
+}
+
+/// Reexpresa `amount` de `from_decimals` a `to_decimals` decimales.
+pub fn rescale(amount: i128, from_decimals: u32, to_decimals: u32, rounding: Rounding) -> Option<i128> {
+    if to_decimals >= from_decimals {
+        amount.checked_mul(pow10(to_decimals - from_decimals)?)
+    } else {
+        div(amount, pow10(from_decimals - to_decimals)?, rounding)
+    }
+}
+
+// Producto completo de dos u128 como (hi, lo).
+fn mul_u128(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+    let ll = a_lo * b_lo;
+    let lh = a_lo * b_hi;
+    let hl = a_hi * b_lo;
+    let hh = a_hi * b_hi;
+
+    // Suma de la columna central con su acarreo
+    let mid = (ll >> 64) + (lh & MASK) + (hl & MASK);
+    let lo = (ll & MASK) | (mid << 64);
+    let hi = hh + (lh >> 64) + (hl >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+// División larga de un u256 (hi, lo) entre un u128 no nulo: (q_hi, q_lo, resto).
+fn div_u256(hi: u128, lo: u128, d: u128) -> (u128, u128, u128) {
+    if hi == 0 {
+        return (0, lo / d, lo % d);
+    }
+    let (mut q_hi, mut q_lo, mut rem) = (0u128, 0u128, 0u128);
+    for i in (0..256).rev() {
+        let bit = if i >= 128 { (hi >> (i - 128)) & 1 } else { (lo >> i) & 1 };
+        // `carry` es el bit 129 del resto desplazado; si está, el resto ya supera a `d`.
+        let carry = rem >> 127;
+        rem = (rem << 1) | bit;
+        if carry == 1 || rem >= d {
+            rem = rem.wrapping_sub(d);
+            if i >= 128 {
+                q_hi |= 1 << (i - 128);
+            } else {
+                q_lo |= 1 << i;
+            }
+        }
+    }
+    (q_hi, q_lo, rem)
+}
+
+mod test;
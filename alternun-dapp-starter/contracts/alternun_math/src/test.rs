@@ -0,0 +1,106 @@
+#![cfg(test)]
+
+extern crate std;
+
+use super::*;
+
+use ::proptest::prelude::*;
+use num_bigint::BigInt;
+use num_integer::Integer;
+
+// Referencia en precisión arbitraria: a * b / d redondeado, o None si no cabe en i128.
+fn reference(a: i128, b: i128, d: i128, rounding: Rounding) -> Option<i128> {
+    if d == 0 {
+        return None;
+    }
+    let n = BigInt::from(a) * BigInt::from(b);
+    let d = BigInt::from(d);
+    let q = match rounding {
+        Rounding::Floor => n.div_floor(&d),
+        Rounding::Ceil => -((-n).div_floor(&d)),
+    };
+    i128::try_from(q).ok()
+}
+
+fn rounding() -> impl Strategy<Value = Rounding> {
+    prop_oneof![Just(Rounding::Floor), Just(Rounding::Ceil)]
+}
+
+// Mezcla valores pequeños, grandes y extremos para cubrir los caminos de 128 y 256 bits.
+fn operand() -> impl Strategy<Value = i128> {
+    prop_oneof![
+        -1_000_000i128..1_000_000,
+        any::<i64>().prop_map(i128::from),
+        any::<i128>(),
+        Just(i128::MAX),
+        Just(i128::MIN),
+        Just(0i128),
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(2000))]
+
+    #[test]
+    fn mul_div_matches_reference(a in operand(), b in operand(), d in operand(), r in rounding()) {
+        prop_assert_eq!(mul_div(a, b, d, r), reference(a, b, d, r));
+    }
+
+    #[test]
+    fn div_matches_reference(a in operand(), d in operand(), r in rounding()) {
+        prop_assert_eq!(div(a, d, r), reference(a, 1, d, r));
+    }
+
+    #[test]
+    fn apply_bps_matches_reference(amount in operand(), bps in 0i128..=BPS, r in rounding()) {
+        prop_assert_eq!(apply_bps(amount, bps, r), reference(amount, bps, BPS, r));
+    }
+
+    #[test]
+    fn floor_and_ceil_differ_only_when_inexact(a in operand(), b in operand(), d in operand()) {
+        if let (Some(lo), Some(hi)) = (mul_div(a, b, d, Rounding::Floor), mul_div(a, b, d, Rounding::Ceil)) {
+            let exact = (BigInt::from(a) * BigInt::from(b)) % BigInt::from(d) == BigInt::from(0);
+            prop_assert_eq!(hi - lo, if exact { 0 } else { 1 });
+        }
+    }
+
+    #[test]
+    fn rescale_matches_reference(amount in operand(), from in 0u32..=40, to in 0u32..=40, r in rounding()) {
+        let expected = if from.abs_diff(to) > MAX_POW10 {
+            None
+        } else if to >= from {
+            let n = BigInt::from(amount) * BigInt::from(10).pow(to - from);
+            i128::try_from(n).ok()
+        } else {
+            reference(amount, 1, 10i128.pow(from - to), r)
+        };
+        prop_assert_eq!(rescale(amount, from, to, r), expected);
+    }
+
+    #[test]
+    fn rescale_round_trip_is_lossless_upwards(amount in -1_000_000_000_000i128..1_000_000_000_000, from in 0u32..=18, extra in 0u32..=18) {
+        let up = rescale(amount, from, from + extra, Rounding::Floor).unwrap();
+        prop_assert_eq!(rescale(up, from + extra, from, Rounding::Floor), Some(amount));
+    }
+}
+
+#[test]
+fn pow10_bounds() {
+    assert_eq!(pow10(0), Some(1));
+    assert_eq!(pow10(7), Some(10_000_000));
+    assert_eq!(pow10(MAX_POW10), Some(10i128.pow(38)));
+    assert_eq!(pow10(MAX_POW10 + 1), None);
+}
+
+#[test]
+fn mul_div_wide_intermediate() {
+    // a * b desborda i128 pero el cociente cabe
+    let a = i128::MAX / 3;
+    assert_eq!(mul_div(a, 6, 3, Rounding::Floor), Some(a * 2));
+    assert_eq!(mul_div(i128::MIN, 1, -1, Rounding::Floor), None);
+    assert_eq!(mul_div(i128::MIN, -1, 1, Rounding::Ceil), None);
+    assert_eq!(mul_div(i128::MIN, 1, 1, Rounding::Floor), Some(i128::MIN));
+    assert_eq!(mul_div(7, 1, 0, Rounding::Floor), None);
+    assert_eq!(mul_div(-7, 1, 2, Rounding::Floor), Some(-4));
+    assert_eq!(mul_div(-7, 1, 2, Rounding::Ceil), Some(-3));
+}
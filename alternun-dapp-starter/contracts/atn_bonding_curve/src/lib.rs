@@ -1,5 +1,6 @@
 #![no_std]
 
+use alternun_math::{apply_bps, mul_div, Rounding, BPS};
 use soroban_sdk::{
     contract, contractimpl,
     Env, Address, Symbol,
@@ -13,9 +14,12 @@ impl State {
     pub const ADMIN: Symbol     = symbol_short!("admin");
     pub const TOKEN_ATN: Symbol = symbol_short!("atn");
     pub const TOKEN_RES: Symbol = symbol_short!("res");
+    pub const SUPPLY: Symbol    = symbol_short!("supply"); // ATN emitido por la curva (unidades base)
+    pub const SPREAD: Symbol    = symbol_short!("spread"); // spread de venta en bps
 }
 
 // Cliente explícito del contrato ATN para poder llamar a `mint`
+#[allow(dead_code)]
 mod atn_token {
     use soroban_sdk::{Env, Address, contractclient};
 
@@ -25,6 +29,78 @@ mod atn_token {
     }
 }
 
+/// Curva de precio del doc de tokenomics, con `s` en ATN enteros y `P` en
+/// unidades de reserva por ATN:
+///
+///   P(s) = 0.02 + 4.95e-9·s − 2.96e-18·s²
+///
+/// ATN y reserva usan 7 decimales. Con `u = s·1e7` (unidades base), el costo en
+/// unidades base de reserva de pasar de `u0` a `u1` es la integral exacta
+///
+///   (u1 − u0) · (1.2e33 + 1.485e19·(u1 + u0) − 592·(u1² + u1·u0 + u0²)) / 6e34
+pub mod curve {
+    use super::*;
+
+    pub const DECIMALS: u32 = 7;
+    /// 1 ATN en unidades base.
+    pub const UNIT: i128 = 10_000_000;
+    /// Supply máximo: el pico de P(s) en s = 4.95e-9 / (2·2.96e-18) ≈ 836_148_648.6
+    /// ATN. Hasta ahí el precio es creciente y los términos caben en i128.
+    pub const MAX_SUPPLY: i128 = 836_148_648 * UNIT;
+
+    const C0: i128 = 1_200_000_000_000_000_000_000_000_000_000_000; // 0.02 · 6e34
+    const C1: i128 = 14_850_000_000_000_000_000;                     // 4.95e-9 / 2 · 6e34 / 1e7
+    const C2: i128 = 592;                                            // 2.96e-18 / 3 · 6e34 / 1e14
+    const DEN: i128 = 60_000_000_000_000_000_000_000_000_000_000_000; // 6e34
+
+    /// Unidades base de reserva entre los supplies `u0 <= u1` (en unidades base de
+    /// ATN), redondeado según `rounding`.
+    pub fn cost_between(u0: i128, u1: i128, rounding: Rounding) -> i128 {
+        if u0 < 0 || u1 < u0 || u1 > MAX_SUPPLY {
+            panic!("supply out of range");
+        }
+        // Con u <= MAX_SUPPLY (< 8.4e15) cada término queda por debajo de 1e36
+        let inner = C0 + C1 * (u1 + u0) - C2 * (u1 * u1 + u1 * u0 + u0 * u0);
+        mul_div(u1 - u0, inner, DEN, rounding).expect("math overflow")
+    }
+}
+
+fn read_supply(e: &Env) -> i128 {
+    e.storage().instance().get(&State::SUPPLY).unwrap_or(0)
+}
+
+fn read_spread_bps(e: &Env) -> u32 {
+    e.storage().instance().get(&State::SPREAD).unwrap_or(0)
+}
+
+// Reserva que paga la venta de `amount_atn` al supply actual, neta del spread.
+// Devuelve (nuevo supply, bruto, neto). El bruto redondea hacia abajo y el spread
+// hacia arriba, así la curva nunca paga de más.
+fn sale(e: &Env, amount_atn: i128) -> (i128, i128, i128) {
+    if amount_atn <= 0 {
+        panic!("amount must be > 0");
+    }
+    let supply = read_supply(e);
+    if amount_atn > supply {
+        panic!("amount exceeds supply");
+    }
+    let new_supply = supply - amount_atn;
+    let gross = curve::cost_between(new_supply, supply, Rounding::Floor);
+    let spread = apply_bps(gross, read_spread_bps(e) as i128, Rounding::Ceil).expect("math overflow");
+    (new_supply, gross, gross - spread)
+}
+
+// Invariante: la reserva alcanza para recomprar todo el supply emitido por la
+// curva. Se sostiene porque las compras cobran redondeando hacia arriba y las
+// ventas pagan redondeando hacia abajo; el spread y cualquier donación sólo suman.
+fn check_coverage(e: &Env) {
+    let token_res: Address = e.storage().instance().get(&State::TOKEN_RES).unwrap();
+    let balance = TokenClient::new(e, &token_res).balance(&e.current_contract_address());
+    if balance < curve::cost_between(0, read_supply(e), Rounding::Floor) {
+        panic!("reserve below curve");
+    }
+}
+
 #[contract]
 pub struct AtnBondingCurve;
 
@@ -36,36 +112,118 @@ impl AtnBondingCurve {
         }
         admin.require_auth();
 
+        // La curva está expresada para 7 decimales en ambos tokens
+        if TokenClient::new(&e, &token_atn).decimals() != curve::DECIMALS
+            || TokenClient::new(&e, &token_res).decimals() != curve::DECIMALS
+        {
+            panic!("tokens must have 7 decimals");
+        }
+
         e.storage().instance().set(&State::ADMIN, &admin);
         e.storage().instance().set(&State::TOKEN_ATN, &token_atn);
         e.storage().instance().set(&State::TOKEN_RES, &token_res);
+        e.storage().instance().set(&State::SUPPLY, &0_i128);
     }
 
-    /// Compra ATN pagando con el token de reserva.
-    /// Asumimos que `amount_atn` ya fue validado/limitado externamente o lo calculas
-    /// con una fórmula de curva. Aquí sólo hacemos el cobro (`transfer`) y el `mint`.
-    pub fn buy(e: Env, payer: Address, amount_atn: i128, cost: i128) {
+    /// Compra `amount_atn` (unidades base) pagando con el token de reserva el costo
+    /// de la curva entre el supply actual y el nuevo, redondeado hacia arriba.
+    /// Falla si el costo supera `max_cost`. Devuelve el costo cobrado.
+    pub fn buy(e: Env, payer: Address, amount_atn: i128, max_cost: i128) -> i128 {
+        payer.require_auth();
+        if amount_atn <= 0 {
+            panic!("amount must be > 0");
+        }
+
+        let supply = read_supply(&e);
+        let new_supply = supply.checked_add(amount_atn).expect("math overflow");
+        let cost = curve::cost_between(supply, new_supply, Rounding::Ceil);
+        if cost > max_cost {
+            panic!("cost exceeds max_cost");
+        }
+        e.storage().instance().set(&State::SUPPLY, &new_supply);
+
         // Cargar direcciones desde storage
         let token_atn: Address = e.storage().instance().get(&State::TOKEN_ATN).unwrap();
         let token_res: Address = e.storage().instance().get(&State::TOKEN_RES).unwrap();
 
         // 1) Cobro del token de reserva: payer -> este contrato
         let res_client = TokenClient::new(&e, &token_res);
-        payer.require_auth();
         res_client.transfer(&payer, &e.current_contract_address(), &cost);
 
         // 2) Mintear ATN al payer
         //    (El contrato token ATN debe ser mintable y tener como admin a este contrato)
         let atn = crate::atn_token::AtnClient::new(&e, &token_atn);
         atn.mint(&payer, &amount_atn);
+        check_coverage(&e);
+
+        e.events().publish((symbol_short!("buy"), payer), (amount_atn, cost));
+        cost
     }
 
-    // (Opcional) función auxiliar de cálculo del costo según la curva.
-    // Puedes adaptar a tu fórmula real del doc de tokenomics:
-    // P(s) = 0.02 + 4.95e-9 * s - 2.96e-18 * s²
-    // Recuerda: implementa fixed-point (enteros) si la usas on-chain.
-    pub fn quote_cost(_e: Env, amount_atn: i128) -> i128 {
-        // Placeholder: 1:1 — reemplazar por la curva fixed-point
-        amount_atn
+    /// Vende `amount_atn` (unidades base) a la curva: quema el ATN de `seller` y le
+    /// paga desde la reserva la integral entre el supply nuevo y el actual
+    /// (redondeada hacia abajo) menos el spread. Falla si lo recibido queda por
+    /// debajo de `min_reserve_out`. Devuelve lo pagado.
+    pub fn sell(e: Env, seller: Address, amount_atn: i128, min_reserve_out: i128) -> i128 {
+        seller.require_auth();
+        let (new_supply, gross, net) = sale(&e, amount_atn);
+        if net < min_reserve_out {
+            panic!("reserve out below minimum");
+        }
+        e.storage().instance().set(&State::SUPPLY, &new_supply);
+
+        let token_atn: Address = e.storage().instance().get(&State::TOKEN_ATN).unwrap();
+        let token_res: Address = e.storage().instance().get(&State::TOKEN_RES).unwrap();
+
+        // 1) Quemar el ATN del seller
+        TokenClient::new(&e, &token_atn).burn(&seller, &amount_atn);
+
+        // 2) Pagar la reserva: este contrato -> seller (el spread queda en la reserva)
+        if net > 0 {
+            TokenClient::new(&e, &token_res).transfer(&e.current_contract_address(), &seller, &net);
+        }
+        check_coverage(&e);
+
+        e.events().publish((symbol_short!("sell"), seller), (amount_atn, gross, net));
+        net
+    }
+
+    /// Reserva neta que pagaría hoy la venta de `amount_atn`.
+    pub fn quote_sale(e: Env, amount_atn: i128) -> i128 {
+        sale(&e, amount_atn).2
+    }
+
+    /// Define el spread de venta en bps (solo admin). Rige para las ventas
+    /// siguientes; las compras no lo pagan.
+    pub fn set_sell_spread(e: Env, admin: Address, spread_bps: u32) {
+        let stored: Address = e.storage().instance().get(&State::ADMIN).unwrap();
+        if admin != stored {
+            panic!("not admin");
+        }
+        admin.require_auth();
+        if spread_bps as i128 > BPS {
+            panic!("invalid spread");
+        }
+        e.storage().instance().set(&State::SPREAD, &spread_bps);
+    }
+
+    pub fn sell_spread_bps(e: Env) -> u32 {
+        read_spread_bps(&e)
+    }
+
+    /// Costo en unidades base de reserva de comprar `amount_atn` al supply actual.
+    pub fn quote_cost(e: Env, amount_atn: i128) -> i128 {
+        if amount_atn <= 0 {
+            panic!("amount must be > 0");
+        }
+        let supply = read_supply(&e);
+        curve::cost_between(supply, supply.checked_add(amount_atn).expect("math overflow"), Rounding::Ceil)
+    }
+
+    /// ATN emitido por la curva, en unidades base.
+    pub fn supply(e: Env) -> i128 {
+        read_supply(&e)
     }
 }
+
+mod test;
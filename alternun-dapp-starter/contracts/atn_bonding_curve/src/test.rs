@@ -0,0 +1,262 @@
+#![cfg(test)]
+
+extern crate std;
+
+use super::*;
+use super::curve::{cost_between, MAX_SUPPLY, UNIT};
+
+use ::proptest::prelude::*;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::token::StellarAssetClient as TokenAdminClient;
+
+// Referencia en precisión arbitraria a partir de los coeficientes decimales del
+// doc: costo = 1e7 · ∫ P(s) ds entre s0 = u0/1e7 y s1 = u1/1e7, con
+// ∫ P = 0.02·s + (4.95e-9/2)·s² − (2.96e-18/3)·s³, redondeado hacia arriba.
+fn reference(u0: i128, u1: i128) -> i128 {
+    let pow10 = |n: u32| BigInt::from(10).pow(n);
+    // (numerador, denominador) de cada coeficiente de la integral y su grado
+    let terms = [
+        (BigInt::from(2), pow10(2), 1u32),                    // 0.02
+        (BigInt::from(495), BigInt::from(2) * pow10(11), 2),  // 4.95e-9 / 2
+        (BigInt::from(-296), BigInt::from(3) * pow10(20), 3), // -2.96e-18 / 3
+    ];
+    let integral = |u: i128| -> (BigInt, BigInt) {
+        let mut num = BigInt::from(0);
+        let mut den = BigInt::from(1);
+        for (c, d, k) in terms.iter() {
+            // c/d · (u/1e7)^k
+            let tn = c * BigInt::from(u).pow(*k);
+            let td = d * pow10(7 * k);
+            num = num * &td + tn * &den;
+            den *= td;
+        }
+        (num, den)
+    };
+    let (n1, d1) = integral(u1);
+    let (n0, d0) = integral(u0);
+    let num = (n1 * &d0 - n0 * &d1) * pow10(7);
+    let den = d1 * d0;
+    i128::try_from(-((-num).div_floor(&den))).unwrap()
+}
+
+struct Setup<'a> {
+    admin: Address,
+    payer: Address,
+    atn: TokenClient<'a>,
+    res: TokenClient<'a>,
+    curve: AtnBondingCurveClient<'a>,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let payer = Address::generate(&env);
+    let curve_id = env.register_contract(None, AtnBondingCurve);
+
+    // El ATN lo mintea la curva
+    let atn = env.register_stellar_asset_contract_v2(curve_id.clone());
+    let res = env.register_stellar_asset_contract_v2(admin.clone());
+    TokenAdminClient::new(&env, &res.address()).mint(&payer, &i128::MAX);
+
+    let curve = AtnBondingCurveClient::new(&env, &curve_id);
+    curve.init(&admin, &atn.address(), &res.address());
+
+    Setup {
+        atn: TokenClient::new(&env, &atn.address()),
+        res: TokenClient::new(&env, &res.address()),
+        admin,
+        payer,
+        curve,
+    }
+}
+
+#[test]
+fn cost_matches_known_vectors() {
+    // 1 ATN al inicio: 0.02 + 2.475e-9 reserva -> 200_000.02475 unidades
+    assert_eq!(cost_between(0, UNIT, Rounding::Ceil), 200_001);
+    assert_eq!(cost_between(0, UNIT, Rounding::Floor), 200_000);
+    assert_eq!(cost_between(0, 0, Rounding::Ceil), 0);
+
+    for (u0, u1) in [
+        (0, 1),
+        (0, 1_000_000 * UNIT),
+        (100_000_000 * UNIT, 100_000_001 * UNIT),
+        (500_000_000 * UNIT, 600_000_000 * UNIT),
+        (0, MAX_SUPPLY),
+        (MAX_SUPPLY - 1, MAX_SUPPLY),
+    ] {
+        assert_eq!(cost_between(u0, u1, Rounding::Ceil), reference(u0, u1));
+    }
+}
+
+#[test]
+fn marginal_price_increases_up_to_max_supply() {
+    let step = MAX_SUPPLY / 64;
+    let mut prev = 0;
+    for i in 0..63 {
+        let u = i * step;
+        let c = cost_between(u, u + UNIT, Rounding::Floor);
+        assert!(c > prev);
+        prev = c;
+    }
+}
+
+#[test]
+#[should_panic(expected = "supply out of range")]
+fn cost_beyond_max_supply_panics() {
+    cost_between(MAX_SUPPLY, MAX_SUPPLY + 1, Rounding::Ceil);
+}
+
+#[test]
+fn buy_charges_integral_and_tracks_supply() {
+    let s = setup();
+    let start = s.res.balance(&s.payer);
+
+    let quote = s.curve.quote_cost(&(10 * UNIT));
+    assert_eq!(quote, reference(0, 10 * UNIT));
+    assert_eq!(s.curve.buy(&s.payer, &(10 * UNIT), &quote), quote);
+    assert_eq!(s.curve.supply(), 10 * UNIT);
+    assert_eq!(s.atn.balance(&s.payer), 10 * UNIT);
+    assert_eq!(s.res.balance(&s.curve.address), quote);
+
+    // La segunda compra cuesta la integral desde el nuevo supply
+    let second = s.curve.buy(&s.payer, &(5 * UNIT), &i128::MAX);
+    assert_eq!(second, reference(10 * UNIT, 15 * UNIT));
+    assert!(second > quote / 2);
+    assert_eq!(start - s.res.balance(&s.payer), quote + second);
+    assert_eq!(s.curve.supply(), 15 * UNIT);
+}
+
+#[test]
+#[should_panic(expected = "cost exceeds max_cost")]
+fn buy_enforces_max_cost() {
+    let s = setup();
+    let quote = s.curve.quote_cost(&UNIT);
+    s.curve.buy(&s.payer, &UNIT, &(quote - 1));
+}
+
+#[test]
+#[should_panic(expected = "supply out of range")]
+fn buy_cannot_exceed_max_supply() {
+    let s = setup();
+    s.curve.buy(&s.payer, &(MAX_SUPPLY + 1), &i128::MAX);
+}
+
+#[test]
+fn sell_pays_integral_and_burns() {
+    let s = setup();
+    let cost = s.curve.buy(&s.payer, &(20 * UNIT), &i128::MAX);
+
+    let quote = s.curve.quote_sale(&(8 * UNIT));
+    assert_eq!(quote, cost_between(12 * UNIT, 20 * UNIT, Rounding::Floor));
+    assert_eq!(s.curve.sell(&s.payer, &(8 * UNIT), &quote), quote);
+    assert_eq!(s.curve.supply(), 12 * UNIT);
+    assert_eq!(s.atn.balance(&s.payer), 12 * UNIT);
+    assert_eq!(s.res.balance(&s.curve.address), cost - quote);
+
+    // Vender el resto deja la reserva en el redondeo acumulado (unas pocas unidades)
+    let rest = s.curve.sell(&s.payer, &(12 * UNIT), &0);
+    assert_eq!(rest, cost_between(0, 12 * UNIT, Rounding::Floor));
+    assert_eq!(s.curve.supply(), 0);
+    let dust = s.res.balance(&s.curve.address);
+    assert!((0..=2).contains(&dust));
+}
+
+#[test]
+fn sell_spread_stays_in_reserve() {
+    let s = setup();
+    s.curve.buy(&s.payer, &(10 * UNIT), &i128::MAX);
+    s.curve.set_sell_spread(&s.admin, &250);
+    assert_eq!(s.curve.sell_spread_bps(), 250);
+
+    let gross = cost_between(6 * UNIT, 10 * UNIT, Rounding::Floor);
+    let spread = apply_bps(gross, 250, Rounding::Ceil).unwrap();
+    let before = s.res.balance(&s.curve.address);
+    assert_eq!(s.curve.sell(&s.payer, &(4 * UNIT), &0), gross - spread);
+    assert_eq!(before - s.res.balance(&s.curve.address), gross - spread);
+    // La reserva cubre el supply restante con el spread de sobra
+    assert!(s.res.balance(&s.curve.address) >= cost_between(0, 6 * UNIT, Rounding::Floor) + spread);
+}
+
+#[test]
+#[should_panic(expected = "reserve out below minimum")]
+fn sell_enforces_min_reserve_out() {
+    let s = setup();
+    s.curve.buy(&s.payer, &UNIT, &i128::MAX);
+    let quote = s.curve.quote_sale(&UNIT);
+    s.curve.sell(&s.payer, &UNIT, &(quote + 1));
+}
+
+#[test]
+#[should_panic(expected = "amount exceeds supply")]
+fn sell_cannot_exceed_supply() {
+    let s = setup();
+    s.curve.buy(&s.payer, &UNIT, &i128::MAX);
+    s.curve.sell(&s.payer, &(UNIT + 1), &0);
+}
+
+#[test]
+#[should_panic(expected = "not admin")]
+fn only_admin_sets_spread() {
+    let s = setup();
+    s.curve.set_sell_spread(&s.payer, &100);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(500))]
+
+    #[test]
+    fn cost_matches_reference(u0 in 0i128..=MAX_SUPPLY, u1 in 0i128..=MAX_SUPPLY) {
+        let (u0, u1) = if u0 <= u1 { (u0, u1) } else { (u1, u0) };
+        let ceil = cost_between(u0, u1, Rounding::Ceil);
+        prop_assert_eq!(ceil, reference(u0, u1));
+        let floor = cost_between(u0, u1, Rounding::Floor);
+        prop_assert!(ceil - floor <= 1);
+    }
+
+    #[test]
+    fn split_buys_never_cost_less(u0 in 0i128..MAX_SUPPLY / 2, a in 1i128..MAX_SUPPLY / 4, b in 1i128..MAX_SUPPLY / 4) {
+        // Comprar en dos partes redondea hacia arriba dos veces: nunca es más barato
+        let whole = cost_between(u0, u0 + a + b, Rounding::Ceil);
+        let split = cost_between(u0, u0 + a, Rounding::Ceil) + cost_between(u0 + a, u0 + a + b, Rounding::Ceil);
+        prop_assert!(split >= whole && split - whole <= 1);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(50))]
+
+    // Tras cualquier secuencia de compras y ventas la reserva cubre la venta de
+    // todo el supply, y venderlo todo nunca deja la reserva en negativo.
+    #[test]
+    fn reserve_covers_full_sell_back(
+        ops in prop::collection::vec((any::<bool>(), 1i128..=50_000_000 * UNIT), 1..12),
+        spread in 0u32..=1_000,
+    ) {
+        let s = setup();
+        s.curve.set_sell_spread(&s.admin, &spread);
+        for (is_buy, amount) in ops {
+            let supply = s.curve.supply();
+            if is_buy {
+                s.curve.buy(&s.payer, &amount, &i128::MAX);
+            } else if supply > 0 {
+                s.curve.sell(&s.payer, &amount.min(supply), &0);
+            }
+            let reserve = s.res.balance(&s.curve.address);
+            prop_assert!(reserve >= cost_between(0, s.curve.supply(), Rounding::Floor));
+        }
+
+        let supply = s.curve.supply();
+        if supply > 0 {
+            s.curve.set_sell_spread(&s.admin, &0);
+            s.curve.sell(&s.payer, &supply, &0);
+        }
+        prop_assert_eq!(s.curve.supply(), 0);
+        prop_assert!(s.res.balance(&s.curve.address) >= 0);
+    }
+}
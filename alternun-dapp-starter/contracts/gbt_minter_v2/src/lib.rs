@@ -1,19 +1,23 @@
 #![no_std]
 
+use alternun_math::{apply_bps, mul_div, pow10, rescale, Rounding, BPS};
 use soroban_sdk::{
-    contract, contractimpl, contracttype, Address, Env,
+    contract, contractimpl, contracttype, Address, Env, Vec,
     token::Client as TokenClient,
 };
 
 // --- Constantes y pesos ---
-const BPS: i128 = 10_000; // 100% = 10_000
 const W_INFERIDOS: i128 = 1_500; // 0.15
 const W_INDICADOS: i128 = 3_000; // 0.30
 const W_MEDIDOS:   i128 = 6_000; // 0.60
 const W_PROBABLES: i128 = 5_000; // 0.50
 const W_PROBADAS:  i128 = 7_000; // 0.70
+const USD_1E7: i128 = 10_000_000; // FX por defecto: 1 unidad de token = 1 USD
+const MAX_PAY_DECIMALS: u32 = 18;
+const MAX_RECEIPTS_PAGE: u32 = 50;
 
 // --- Oracle client ---
+#[allow(dead_code)]
 mod oracle {
     use soroban_sdk::{Env, contractclient};
     #[contractclient(name = "OracleClient")]
@@ -23,22 +27,43 @@ mod oracle {
 }
 
 // --- Treasury client ---
+#[allow(dead_code)]
 mod treasury {
-    use soroban_sdk::{Env, Address, contractclient};
+    use soroban_sdk::{Env, Address, Vec, contractclient};
     #[contractclient(name = "TreasuryClient")]
     pub trait Treasury {
-        fn route(e: Env, token: Address, from: Address, amount: i128);
+        // `source` = este minter (registrado en el treasury); `reference` = seq del recibo.
+        // Devuelve (destino, monto) de cada split realizado
+        fn route(e: Env, source: Address, token: Address, from: Address, amount: i128, reference: u64) -> Vec<(Address, i128)>;
     }
 }
 
 // --- GBT token client (expone mint) ---
 // Importante: el admin del token debe ser ESTE contrato (el minter), así el mint
 // no requiere la firma de un G-address en cada llamada.
+#[allow(dead_code)]
 mod gbt_token {
     use soroban_sdk::{Env, Address, contractclient};
     #[contractclient(name = "GbtTokenClient")]
     pub trait GbtToken {
         fn mint(e: Env, to: Address, amount: i128);
+        fn decimals(e: Env) -> u32;
+    }
+}
+
+// --- Conversión de unidades ---
+// El oro se contabiliza en gramos con GM_DECIMALS decimales (g*1000) y 1 GBT entero
+// representa 1 gramo, con los decimales que declare el token. Toda conversión entre
+// g*1000 y unidades del token pasa por aquí.
+mod units {
+    use alternun_math::{rescale, Rounding};
+
+    pub const GM_DECIMALS: u32 = 3;
+    pub const GM_PER_GRAM: i128 = 1_000; // 10^GM_DECIMALS
+    pub const MIN_MINT_GM: i128 = GM_PER_GRAM; // mínimo 1 gramo
+
+    pub fn gm_to_gbt_units(gm: i128, gbt_decimals: u32) -> i128 {
+        rescale(gm, GM_DECIMALS, gbt_decimals, Rounding::Floor).expect("math overflow")
     }
 }
 
@@ -54,11 +79,22 @@ pub struct MineData {
     pub enabled: bool,
 }
 
+// Configuración de un token de pago aceptado (USDC, EURC, ...)
+#[contracttype]
+#[derive(Clone)]
+pub struct PayToken {
+    pub decimals: u32,              // leído de `decimals()` del token al registrarlo
+    pub fx_oracle: Option<Address>, // oracle USD por unidad de token (1e7); None = 1 USD
+    pub fee_bps: Option<u32>,       // override del fee global
+}
+
 #[contracttype]
 pub enum DataKey {
     Admin,
     TokenGbt,
-    TokenStable,
+    GbtDecimals,       // decimales del token GBT, leídos en init
+    PayTokens,         // Vec<Address> de tokens de pago aceptados
+    PayToken(Address), // PayToken por token
     Treasury,
     Oracle,
     FeeBps,   // fee de minteo en bps (def 200 = 2%)
@@ -66,41 +102,149 @@ pub enum DataKey {
     Paused,
     MintedGm, // total minteado (g*1000)
     Mine(u32),
+    MineMintedGm(u32),       // minteado atribuido a cada mina (g*1000)
+    ReceiptSeq,              // último seq de recibo emitido
+    Receipt(u64),            // MintReceipt por seq (persistent)
+    PayerReceiptCount(Address),   // cantidad de recibos por payer (persistent)
+    PayerReceipt(Address, u32),   // n-ésimo seq pagado por payer (persistent)
 }
 
 fn read_admin(e: &Env) -> Address { e.storage().instance().get(&DataKey::Admin).unwrap() }
 fn read_token_gbt(e: &Env) -> Address { e.storage().instance().get(&DataKey::TokenGbt).unwrap() }
-fn read_token_stable(e: &Env) -> Address { e.storage().instance().get(&DataKey::TokenStable).unwrap() }
+fn read_gbt_decimals(e: &Env) -> u32 {
+    match e.storage().instance().get(&DataKey::GbtDecimals) {
+        Some(d) => d,
+        None => panic!("gbt decimals not set"),
+    }
+}
 fn read_treasury(e: &Env) -> Address { e.storage().instance().get(&DataKey::Treasury).unwrap() }
 fn read_oracle(e: &Env) -> Address { e.storage().instance().get(&DataKey::Oracle).unwrap() }
 fn read_fcbps(e: &Env) -> i128 { e.storage().instance().get(&DataKey::FcBps).unwrap() }
 fn read_fee_bps(e: &Env) -> i128 { e.storage().instance().get(&DataKey::FeeBps).unwrap() }
 
+fn read_pay_token(e: &Env, token: &Address) -> PayToken {
+    match e.storage().instance().get(&DataKey::PayToken(token.clone())) {
+        Some(cfg) => cfg,
+        None => panic!("pay token not allowed"),
+    }
+}
+
+// Lee `decimals()` del token GBT y valida que un g*1000 se represente sin pérdida
+// (decimales >= GM_DECIMALS) y que la conversión no desborde.
+fn write_gbt_decimals(e: &Env, token_gbt: &Address) {
+    let decimals = crate::gbt_token::GbtTokenClient::new(e, token_gbt).decimals();
+    if decimals < units::GM_DECIMALS || rescale(1, units::GM_DECIMALS, decimals, Rounding::Floor).is_none() {
+        panic!("gbt decimals incompatible");
+    }
+    e.storage().instance().set(&DataKey::GbtDecimals, &decimals);
+}
+
+fn read_pay_tokens(e: &Env) -> Vec<Address> {
+    e.storage().instance().get(&DataKey::PayTokens).unwrap_or(Vec::new(e))
+}
+
+fn write_pay_token(e: &Env, token: &Address, fx_oracle: Option<Address>, fee_bps: Option<u32>) {
+    let decimals = TokenClient::new(e, token).decimals();
+    if decimals > MAX_PAY_DECIMALS { panic!("unsupported decimals"); }
+    if let Some(f) = fee_bps {
+        if f as i128 >= BPS { panic!("bad fee"); }
+    }
+    let cfg = PayToken { decimals, fx_oracle, fee_bps };
+    e.storage().instance().set(&DataKey::PayToken(token.clone()), &cfg);
+
+    let mut list = read_pay_tokens(e);
+    if !list.contains(token) {
+        list.push_back(token.clone());
+        e.storage().instance().set(&DataKey::PayTokens, &list);
+    }
+}
+
+// Parámetros de cotización de un token de pago: (fee_bps, fx USD/unidad 1e7, 10^decimals)
+fn pay_terms(e: &Env, cfg: &PayToken) -> (i128, i128, i128) {
+    let fee_bps = match cfg.fee_bps {
+        Some(f) => f as i128,
+        None => read_fee_bps(e),
+    };
+    let fx_1e7 = match &cfg.fx_oracle {
+        Some(o) => crate::oracle::OracleClient::new(e, o).get_price(),
+        None => USD_1E7,
+    };
+    (fee_bps, fx_1e7, pow10(cfg.decimals).expect("math overflow"))
+}
+
 fn capacity_of_mine(m: &MineData, fc_bps: i128) -> i128 {
-    let sum = m.inferidos_gm * W_INFERIDOS
-        + m.indicados_gm * W_INDICADOS
-        + m.medidos_gm   * W_MEDIDOS
-        + m.probables_gm * W_PROBABLES
-        + m.probadas_gm  * W_PROBADAS;
-    (sum * fc_bps) / (BPS * BPS)
+    let weighted = [
+        (m.inferidos_gm, W_INFERIDOS),
+        (m.indicados_gm, W_INDICADOS),
+        (m.medidos_gm,   W_MEDIDOS),
+        (m.probables_gm, W_PROBABLES),
+        (m.probadas_gm,  W_PROBADAS),
+    ];
+    let mut sum: i128 = 0;
+    for (gm, w) in weighted {
+        sum = gm.checked_mul(w).and_then(|v| sum.checked_add(v)).expect("math overflow");
+    }
+    mul_div(sum, fc_bps, BPS * BPS, Rounding::Floor).expect("math overflow")
 }
 
 #[contracttype]
 #[derive(Clone)]
 pub struct Preview {
     pub gbt_out_gm: i128,        // gramos*1000
-    pub net_stable_1e7: i128,    // neto tras fee
-    pub fee_stable_1e7: i128,    // fee cobrado
+    pub net_stable: i128,        // neto tras fee (unidades del token de pago)
+    pub fee_stable: i128,        // fee cobrado (unidades del token de pago)
     pub price_1e7: i128,         // USD/g (1e7)
+    pub fx_1e7: i128,            // USD por unidad de token de pago (1e7)
     pub meets_min: bool,         // >= 1.000 gbt (1 gramo)
     pub capacity_left_gm: i128,  // capacidad restante (g*1000)
 }
 
+// Recibo de un mint liquidado; se persiste por `seq`
+#[contracttype]
+#[derive(Clone)]
+pub struct MintReceipt {
+    pub seq: u64,
+    pub payer: Address,
+    pub recipient: Address,
+    pub pay_token: Address,
+    pub gbt_out_gm: i128,        // gramos*1000 entregados
+    pub gbt_units: i128,         // unidades del token GBT minteadas
+    pub stable_charged: i128,    // neto + fee (unidades del token de pago)
+    pub fee_stable: i128,        // fee cobrado
+    pub price_1e7: i128,         // USD/g (1e7) usado
+    pub fx_1e7: i128,            // USD por unidad de token de pago (1e7) usado
+    pub treasury_splits: Vec<(Address, i128)>, // (destino, monto) repartido por Treasury.route
+    pub mines: Vec<(u32, i128)>, // (mina, g*1000) consumidos
+    pub ledger: u32,
+}
+
+// Cotización validada lista para liquidar
+struct Fill {
+    gbt_out_gm: i128,
+    net_stable: i128,
+    fee_stable: i128,
+    price_1e7: i128,
+    fx_1e7: i128,
+}
+
+impl From<&Preview> for Fill {
+    fn from(p: &Preview) -> Self {
+        Fill {
+            gbt_out_gm: p.gbt_out_gm,
+            net_stable: p.net_stable,
+            fee_stable: p.fee_stable,
+            price_1e7: p.price_1e7,
+            fx_1e7: p.fx_1e7,
+        }
+    }
+}
+
 #[contract]
 pub struct GbtMinterV2;
 
 #[contractimpl]
 impl GbtMinterV2 {
+    #[allow(clippy::too_many_arguments)]
     pub fn init(
         e: Env,
         admin: Address,
@@ -116,11 +260,13 @@ impl GbtMinterV2 {
 
         e.storage().instance().set(&DataKey::Admin, &admin);
         e.storage().instance().set(&DataKey::TokenGbt, &token_gbt);
-        e.storage().instance().set(&DataKey::TokenStable, &token_stable);
+        write_gbt_decimals(&e, &token_gbt);
+        write_pay_token(&e, &token_stable, None, None);
         e.storage().instance().set(&DataKey::Treasury, &treasury);
         e.storage().instance().set(&DataKey::Oracle, &oracle);
 
         let fee = if fee_bps == 0 { 200 } else { fee_bps } as i128;
+        if fee >= BPS { panic!("bad fee"); }
         let fc  = if fc_bps  == 0 { 8000 } else { fc_bps } as i128;
         e.storage().instance().set(&DataKey::FeeBps, &fee);
         e.storage().instance().set(&DataKey::FcBps, &fc);
@@ -135,6 +281,7 @@ impl GbtMinterV2 {
 
     pub fn set_fee_bps(e: Env, fee_bps: u32) {
         let admin = read_admin(&e); admin.require_auth();
+        if fee_bps as i128 >= BPS { panic!("bad fee"); }
         e.storage().instance().set(&DataKey::FeeBps, &(fee_bps as i128));
     }
 
@@ -143,6 +290,42 @@ impl GbtMinterV2 {
         e.storage().instance().set(&DataKey::Paused, &paused);
     }
 
+    /// Vuelve a leer los decimales del token GBT (instancias inicializadas antes de
+    /// guardarlos en init).
+    pub fn sync_gbt_decimals(e: Env) {
+        let admin = read_admin(&e); admin.require_auth();
+        write_gbt_decimals(&e, &read_token_gbt(&e));
+    }
+
+    pub fn gbt_decimals(e: Env) -> u32 {
+        read_gbt_decimals(&e)
+    }
+
+    /// Acepta (o reconfigura) un token de pago. Los decimales se leen del propio token.
+    pub fn set_pay_token(e: Env, token: Address, fx_oracle: Option<Address>, fee_bps: Option<u32>) {
+        let admin = read_admin(&e); admin.require_auth();
+        write_pay_token(&e, &token, fx_oracle, fee_bps);
+    }
+
+    pub fn remove_pay_token(e: Env, token: Address) {
+        let admin = read_admin(&e); admin.require_auth();
+        e.storage().instance().remove(&DataKey::PayToken(token.clone()));
+        let mut list = read_pay_tokens(&e);
+        if let Some(i) = list.first_index_of(&token) {
+            list.remove(i);
+            e.storage().instance().set(&DataKey::PayTokens, &list);
+        }
+    }
+
+    pub fn pay_tokens(e: Env) -> Vec<Address> {
+        read_pay_tokens(&e)
+    }
+
+    pub fn get_pay_token(e: Env, token: Address) -> PayToken {
+        read_pay_token(&e, &token)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn upsert_mine(
         e: Env,
         id: u32,
@@ -191,70 +374,300 @@ impl GbtMinterV2 {
         if avail < 0 { 0 } else { avail }
     }
 
-    pub fn preview_mint(e: Env, amount_stable_1e7: i128, max_id_inclusive: u32) -> Preview {
-        let fee_bps = read_fee_bps(&e);
+    pub fn preview_mint(e: Env, pay_token: Address, amount_stable: i128, max_id_inclusive: u32) -> Preview {
+        let (fee_bps, fx_1e7, unit) = pay_terms(&e, &read_pay_token(&e, &pay_token));
         let price_1e7 = crate::oracle::OracleClient::new(&e, &read_oracle(&e)).get_price();
         let avail = Self::available_capacity_gm(e.clone(), max_id_inclusive);
 
-        if amount_stable_1e7 <= 0 || price_1e7 <= 0 || avail <= 0 {
+        if amount_stable <= 0 || price_1e7 <= 0 || fx_1e7 <= 0 || avail <= 0 {
             return Preview {
-                gbt_out_gm: 0, net_stable_1e7: 0, fee_stable_1e7: 0,
-                price_1e7, meets_min: false, capacity_left_gm: avail.max(0)
+                gbt_out_gm: 0, net_stable: 0, fee_stable: 0,
+                price_1e7, fx_1e7, meets_min: false, capacity_left_gm: avail.max(0)
             };
         }
 
-        let fee = (amount_stable_1e7 * fee_bps) / BPS;
-        let net = amount_stable_1e7 - fee;
+        let fee = apply_bps(amount_stable, fee_bps, Rounding::Floor).expect("math overflow");
+        let net = amount_stable - fee;
 
-        // gbt_out_gm = floor((net / price) * 1000)
-        let mut gbt_out_gm = (net * 1000) / price_1e7;
-        let meets_min = gbt_out_gm >= 1000; // mínimo 1 gramo
+        let mut gbt_out_gm = gbt_out_for(net, fx_1e7, unit, price_1e7);
+        let meets_min = gbt_out_gm >= units::MIN_MINT_GM;
         if !meets_min { gbt_out_gm = 0; }
         if gbt_out_gm > avail { gbt_out_gm = avail; }
 
         let cap_left = if avail >= gbt_out_gm { avail - gbt_out_gm } else { 0 };
         Preview {
             gbt_out_gm,
-            net_stable_1e7: net,
-            fee_stable_1e7: fee,
+            net_stable: net,
+            fee_stable: fee,
             price_1e7,
+            fx_1e7,
             meets_min,
             capacity_left_gm: cap_left
         }
     }
 
-    pub fn mint(e: Env, payer: Address, amount_stable_1e7: i128, max_id_inclusive: u32) {
+    /// Cotiza cuánto `pay_token` hay que pagar (fee incluido) para recibir exactamente
+    /// `gbt_out_gm` (g*1000). Redondea hacia arriba: el payer nunca paga de menos.
+    pub fn preview_mint_exact_out(e: Env, pay_token: Address, gbt_out_gm: i128, max_id_inclusive: u32) -> Preview {
+        let (fee_bps, fx_1e7, unit) = pay_terms(&e, &read_pay_token(&e, &pay_token));
+        let price_1e7 = crate::oracle::OracleClient::new(&e, &read_oracle(&e)).get_price();
+        let avail = Self::available_capacity_gm(e.clone(), max_id_inclusive);
+
+        if gbt_out_gm <= 0 || price_1e7 <= 0 || fx_1e7 <= 0 || fee_bps >= BPS {
+            return Preview {
+                gbt_out_gm: 0, net_stable: 0, fee_stable: 0,
+                price_1e7, fx_1e7, meets_min: false, capacity_left_gm: avail.max(0)
+            };
+        }
+
+        // net = ceil(gbt_out_gm * price * 10^dec / (fx * 1000)) -> el forward da >= gbt_out_gm
+        let net = price_1e7.checked_mul(unit)
+            .and_then(|num| mul_div(gbt_out_gm, num, fx_1e7 * units::GM_PER_GRAM, Rounding::Ceil))
+            .expect("math overflow");
+        // bruto = ceil(net * BPS / (BPS - fee)) -> bruto - floor(bruto * fee / BPS) >= net
+        let gross = mul_div(net, BPS, BPS - fee_bps, Rounding::Ceil).expect("math overflow");
+        let fee = gross - net;
+
+        let meets_min = gbt_out_gm >= units::MIN_MINT_GM;
+        // Si no cabe en la capacidad no se puede servir: no se recorta como en preview_mint
+        let fits = gbt_out_gm <= avail;
+        let out = if meets_min && fits { gbt_out_gm } else { 0 };
+
+        Preview {
+            gbt_out_gm: out,
+            net_stable: net,
+            fee_stable: fee,
+            price_1e7,
+            fx_1e7,
+            meets_min,
+            capacity_left_gm: if fits { avail - gbt_out_gm } else { avail.max(0) }
+        }
+    }
+
+    pub fn mint(e: Env, payer: Address, pay_token: Address, amount_stable: i128, max_id_inclusive: u32) -> MintReceipt {
         // 0) Pausa y auth del payer (clave para las transferencias desde su cuenta)
         let paused = e.storage().instance().get::<_, bool>(&DataKey::Paused).unwrap_or(false);
         if paused { panic!("mint paused"); }
         payer.require_auth();
 
         // 1) Preview y validaciones
-        let p = Self::preview_mint(e.clone(), amount_stable_1e7, max_id_inclusive);
+        let p = Self::preview_mint(e.clone(), pay_token.clone(), amount_stable, max_id_inclusive);
         if !p.meets_min || p.gbt_out_gm <= 0 { panic!("below minimum or zero"); }
 
-        // 2) Fee al admin y neto a Treasury.route (ambos DESDE payer)
-        let stable = TokenClient::new(&e, &read_token_stable(&e));
-        let admin = read_admin(&e);
+        settle_mint(&e, &payer, &payer, &pay_token, &Fill::from(&p), max_id_inclusive)
+    }
 
-        if p.fee_stable_1e7 > 0 {
-            stable.transfer(&payer, &admin, &p.fee_stable_1e7);
+    /// Paga desde `payer` y entrega el GBT a `recipient`.
+    pub fn mint_to(
+        e: Env,
+        payer: Address,
+        recipient: Address,
+        pay_token: Address,
+        amount_stable: i128,
+        max_id_inclusive: u32,
+    ) -> MintReceipt {
+        let mut legs = Vec::new(&e);
+        legs.push_back((recipient, amount_stable));
+        Self::mint_batch(e, payer, pay_token, legs, max_id_inclusive).get(0).unwrap()
+    }
+
+    /// Paga desde `payer` y entrega a varios destinatarios `(recipient, amount_stable)`.
+    /// Todos los tramos se cotizan con el mismo precio y deben caber completos en la
+    /// capacidad disponible: si alguno no cumple, falla el lote entero.
+    pub fn mint_batch(
+        e: Env,
+        payer: Address,
+        pay_token: Address,
+        legs: Vec<(Address, i128)>,
+        max_id_inclusive: u32,
+    ) -> Vec<MintReceipt> {
+        let paused = e.storage().instance().get::<_, bool>(&DataKey::Paused).unwrap_or(false);
+        if paused { panic!("mint paused"); }
+        payer.require_auth();
+        if legs.is_empty() { panic!("empty batch"); }
+
+        let (fee_bps, fx_1e7, unit) = pay_terms(&e, &read_pay_token(&e, &pay_token));
+        let price_1e7 = crate::oracle::OracleClient::new(&e, &read_oracle(&e)).get_price();
+        if price_1e7 <= 0 || fx_1e7 <= 0 { panic!("bad price"); }
+
+        // 1) Cotiza todos los tramos antes de mover fondos
+        let mut total_out_gm: i128 = 0;
+        for (_, amount_stable) in legs.iter() {
+            if amount_stable <= 0 { panic!("bad amount"); }
+            let f = quote_leg(amount_stable, fee_bps, fx_1e7, unit, price_1e7);
+            if f.gbt_out_gm < units::MIN_MINT_GM { panic!("below minimum or zero"); }
+            total_out_gm += f.gbt_out_gm;
         }
-        if p.net_stable_1e7 > 0 {
-            // El treasury hará los splits (50/30/20) usando `from = payer`
-            let tres = crate::treasury::TreasuryClient::new(&e, &read_treasury(&e));
-            tres.route(&read_token_stable(&e), &payer, &p.net_stable_1e7);
+        if total_out_gm > Self::available_capacity_gm(e.clone(), max_id_inclusive) {
+            panic!("insufficient capacity");
         }
 
-        // 3) Minteo GBT al payer
-        // gbt_out_gm (g*1000) -> unidades del token (7 dec): *10^4
-        let gbt_units = p.gbt_out_gm * 10_000;
-        let gbt_addr = read_token_gbt(&e);
-        let gbt = crate::gbt_token::GbtTokenClient::new(&e, &gbt_addr);
-        gbt.mint(&payer, &gbt_units);
+        // 2) Liquida cada tramo
+        let mut receipts = Vec::new(&e);
+        for (recipient, amount_stable) in legs.iter() {
+            let f = quote_leg(amount_stable, fee_bps, fx_1e7, unit, price_1e7);
+            receipts.push_back(settle_mint(&e, &payer, &recipient, &pay_token, &f, max_id_inclusive));
+        }
+        receipts
+    }
+
+    /// Mintea exactamente `gbt_out_gm` (g*1000) cobrando como máximo `max_stable_in`
+    /// (fee incluido, unidades de `pay_token`).
+    pub fn mint_exact_out(
+        e: Env,
+        payer: Address,
+        pay_token: Address,
+        gbt_out_gm: i128,
+        max_stable_in: i128,
+        max_id_inclusive: u32,
+    ) -> MintReceipt {
+        let paused = e.storage().instance().get::<_, bool>(&DataKey::Paused).unwrap_or(false);
+        if paused { panic!("mint paused"); }
+        payer.require_auth();
 
-        // 4) Acumula minteado
-        let prev = e.storage().instance().get::<_, i128>(&DataKey::MintedGm).unwrap_or(0);
-        e.storage().instance().set(&DataKey::MintedGm, &(prev + p.gbt_out_gm));
+        let p = Self::preview_mint_exact_out(e.clone(), pay_token.clone(), gbt_out_gm, max_id_inclusive);
+        if !p.meets_min { panic!("below minimum or zero"); }
+        if p.gbt_out_gm != gbt_out_gm { panic!("insufficient capacity"); }
+        if p.net_stable + p.fee_stable > max_stable_in { panic!("max_stable_in exceeded"); }
+
+        settle_mint(&e, &payer, &payer, &pay_token, &Fill::from(&p), max_id_inclusive)
+    }
+
+    pub fn get_receipt(e: Env, seq: u64) -> Option<MintReceipt> {
+        e.storage().persistent().get(&DataKey::Receipt(seq))
+    }
+
+    pub fn receipt_count(e: Env, payer: Address) -> u32 {
+        read_receipt_count(&e, &payer)
+    }
+
+    /// Seqs de los recibos pagados por `payer`, en orden de emisión, desde el índice
+    /// `start` y hasta `limit` (máx. 50) por página.
+    pub fn receipts_of(e: Env, payer: Address, start: u32, limit: u32) -> Vec<u64> {
+        let end = read_receipt_count(&e, &payer).min(start.saturating_add(limit.min(MAX_RECEIPTS_PAGE)));
+        let mut seqs = Vec::new(&e);
+        for n in start..end {
+            seqs.push_back(e.storage().persistent().get(&DataKey::PayerReceipt(payer.clone(), n)).unwrap());
+        }
+        seqs
+    }
+
+    pub fn mine_minted_gm(e: Env, id: u32) -> i128 {
+        e.storage().instance().get(&DataKey::MineMintedGm(id)).unwrap_or(0)
+    }
+}
+
+// usd_1e7 = net * fx / 10^dec ; gbt_out_gm = floor((usd / price) * 1000)
+fn gbt_out_for(net: i128, fx_1e7: i128, unit: i128, price_1e7: i128) -> i128 {
+    unit.checked_mul(price_1e7)
+        .and_then(|den| mul_div(net, fx_1e7 * units::GM_PER_GRAM, den, Rounding::Floor))
+        .expect("math overflow")
+}
+
+fn quote_leg(amount_stable: i128, fee_bps: i128, fx_1e7: i128, unit: i128, price_1e7: i128) -> Fill {
+    let fee = apply_bps(amount_stable, fee_bps, Rounding::Floor).expect("math overflow");
+    let net = amount_stable - fee;
+    Fill {
+        gbt_out_gm: gbt_out_for(net, fx_1e7, unit, price_1e7),
+        net_stable: net,
+        fee_stable: fee,
+        price_1e7,
+        fx_1e7,
+    }
+}
+
+// Reparte `gbt_out_gm` entre las minas habilitadas por orden de id según su capacidad
+// restante. La capacidad global ya fue validada, así que siempre alcanza.
+fn draw_mines(e: &Env, gbt_out_gm: i128, max_id_inclusive: u32) -> Vec<(u32, i128)> {
+    let fc = read_fcbps(e);
+    let mut drawn = Vec::new(e);
+    let mut left = gbt_out_gm;
+    let mut i: u32 = 0;
+    while left > 0 && i <= max_id_inclusive {
+        if let Some(m) = e.storage().instance().get::<_, MineData>(&DataKey::Mine(i)) {
+            let used = e.storage().instance().get::<_, i128>(&DataKey::MineMintedGm(i)).unwrap_or(0);
+            let room = if m.enabled { capacity_of_mine(&m, fc) - used } else { 0 };
+            if room > 0 {
+                let take = room.min(left);
+                e.storage().instance().set(&DataKey::MineMintedGm(i), &(used + take));
+                drawn.push_back((i, take));
+                left -= take;
+            }
+        }
+        i += 1;
+    }
+    drawn
+}
+
+fn read_receipt_count(e: &Env, payer: &Address) -> u32 {
+    e.storage().persistent().get(&DataKey::PayerReceiptCount(payer.clone())).unwrap_or(0)
+}
+
+// Cada seq va en su propia entrada: el índice por payer no crece dentro de una clave
+fn store_receipt(e: &Env, r: &MintReceipt) {
+    e.storage().persistent().set(&DataKey::Receipt(r.seq), r);
+    let n = read_receipt_count(e, &r.payer);
+    e.storage().persistent().set(&DataKey::PayerReceipt(r.payer.clone(), n), &r.seq);
+    e.storage().persistent().set(&DataKey::PayerReceiptCount(r.payer.clone()), &(n + 1));
+}
+
+// Cobra fee + neto desde `payer`, mintea GBT a `recipient`, acumula lo minteado y
+// persiste el recibo.
+fn settle_mint(
+    e: &Env,
+    payer: &Address,
+    recipient: &Address,
+    pay_token: &Address,
+    f: &Fill,
+    max_id_inclusive: u32,
+) -> MintReceipt {
+    // Fee al admin y neto a Treasury.route (ambos DESDE payer)
+    let stable = TokenClient::new(e, pay_token);
+    let admin = read_admin(e);
+
+    if f.fee_stable > 0 {
+        stable.transfer(payer, &admin, &f.fee_stable);
     }
+    // El seq del recibo es la referencia del pago en el treasury
+    let seq = e.storage().instance().get::<_, u64>(&DataKey::ReceiptSeq).unwrap_or(0) + 1;
+    e.storage().instance().set(&DataKey::ReceiptSeq, &seq);
+
+    let treasury_splits = if f.net_stable > 0 {
+        // El treasury hará los splits según su tabla usando `from = payer`
+        let tres = crate::treasury::TreasuryClient::new(e, &read_treasury(e));
+        tres.route(&e.current_contract_address(), pay_token, payer, &f.net_stable, &seq)
+    } else {
+        Vec::new(e)
+    };
+
+    // Minteo GBT al destinatario
+    let gbt_units = units::gm_to_gbt_units(f.gbt_out_gm, read_gbt_decimals(e));
+    let gbt_addr = read_token_gbt(e);
+    let gbt = crate::gbt_token::GbtTokenClient::new(e, &gbt_addr);
+    gbt.mint(recipient, &gbt_units);
+
+    // Acumula minteado
+    let prev = e.storage().instance().get::<_, i128>(&DataKey::MintedGm).unwrap_or(0);
+    e.storage().instance().set(&DataKey::MintedGm, &(prev + f.gbt_out_gm));
+    let mines = draw_mines(e, f.gbt_out_gm, max_id_inclusive);
+
+    let receipt = MintReceipt {
+        seq,
+        payer: payer.clone(),
+        recipient: recipient.clone(),
+        pay_token: pay_token.clone(),
+        gbt_out_gm: f.gbt_out_gm,
+        gbt_units,
+        stable_charged: f.net_stable + f.fee_stable,
+        fee_stable: f.fee_stable,
+        price_1e7: f.price_1e7,
+        fx_1e7: f.fx_1e7,
+        treasury_splits,
+        mines,
+        ledger: e.ledger().sequence(),
+    };
+    store_receipt(e, &receipt);
+    receipt
 }
+
+mod test;
@@ -0,0 +1,277 @@
+#![cfg(test)]
+
+extern crate std;
+
+use super::*;
+
+use ::proptest::prelude::*;
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::token::StellarAssetClient as TokenAdminClient;
+use soroban_sdk::{contract, contractimpl, symbol_short};
+
+// Oracle de prueba: precio fijo en 1e7
+#[contract]
+pub struct TestOracle;
+
+#[contractimpl]
+impl TestOracle {
+    pub fn set_price(e: Env, price: i128) {
+        e.storage().instance().set(&symbol_short!("price"), &price);
+    }
+
+    pub fn get_price(e: Env) -> i128 {
+        e.storage().instance().get(&symbol_short!("price")).unwrap_or(0)
+    }
+}
+
+// Token de pago de prueba: sólo expone `decimals`, alcanza para cotizar
+#[contract]
+pub struct TestPayToken;
+
+#[contractimpl]
+impl TestPayToken {
+    pub fn set_decimals(e: Env, decimals: u32) {
+        e.storage().instance().set(&symbol_short!("dec"), &decimals);
+    }
+
+    pub fn decimals(e: Env) -> u32 {
+        e.storage().instance().get(&symbol_short!("dec")).unwrap_or(7)
+    }
+}
+
+// Treasury de prueba: cobra el neto desde `from` y lo guarda
+#[contract]
+pub struct TestTreasury;
+
+#[contractimpl]
+impl TestTreasury {
+    pub fn route(e: Env, _source: Address, token: Address, from: Address, amount: i128, _reference: u64) -> Vec<(Address, i128)> {
+        TokenClient::new(&e, &token).transfer(&from, &e.current_contract_address(), &amount);
+        let mut splits = Vec::new(&e);
+        splits.push_back((e.current_contract_address(), amount));
+        splits
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    payer: Address,
+    stable: TokenClient<'a>,
+    gbt: TokenClient<'a>,
+    oracle: TestOracleClient<'a>,
+    minter: GbtMinterV2Client<'a>,
+}
+
+// Precio por defecto: 100 USD/g
+const PRICE_1E7: i128 = 1_000_000_000;
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let payer = Address::generate(&env);
+    let minter_id = env.register_contract(None, GbtMinterV2);
+
+    // El GBT lo mintea el minter
+    let gbt = env.register_stellar_asset_contract_v2(minter_id.clone());
+    let stable = env.register_stellar_asset_contract_v2(admin.clone());
+    TokenAdminClient::new(&env, &stable.address()).mint(&payer, &(i128::MAX / 2));
+
+    let oracle = TestOracleClient::new(&env, &env.register_contract(None, TestOracle));
+    oracle.set_price(&PRICE_1E7);
+    let treasury = env.register_contract(None, TestTreasury);
+
+    let minter = GbtMinterV2Client::new(&env, &minter_id);
+    minter.init(&admin, &gbt.address(), &stable.address(), &treasury, &oracle.address, &0, &0);
+    // Una mina con capacidad de sobra
+    minter.upsert_mine(&0, &0, &0, &0, &0, &1_000_000_000_000_000_000_000, &true);
+
+    Setup {
+        payer,
+        stable: TokenClient::new(&env, &stable.address()),
+        gbt: TokenClient::new(&env, &gbt.address()),
+        oracle,
+        minter,
+        env,
+    }
+}
+
+// Registra un token de pago de `decimals` con un oracle FX propio.
+fn pay_token(s: &Setup, decimals: u32, fx_1e7: i128, fee_bps: Option<u32>) -> Address {
+    let token = TestPayTokenClient::new(&s.env, &s.env.register_contract(None, TestPayToken));
+    token.set_decimals(&decimals);
+    let fx = TestOracleClient::new(&s.env, &s.env.register_contract(None, TestOracle));
+    fx.set_price(&fx_1e7);
+    s.minter.set_pay_token(&token.address, &Some(fx.address), &fee_bps);
+    token.address
+}
+
+#[test]
+fn exact_out_quote_covers_requested_amount() {
+    let s = setup();
+    // 12.345 g a 100 USD/g con fee 2% en un token de 7 decimales a 1 USD
+    let p = s.minter.preview_mint_exact_out(&s.stable.address, &12_345, &0);
+    assert_eq!(p.gbt_out_gm, 12_345);
+    assert_eq!(p.net_stable, 12_345_000_000);
+    // ceil(12_345_000_000 * 10_000 / 9_800)
+    assert_eq!(p.net_stable + p.fee_stable, 12_596_938_776);
+
+    let gross = p.net_stable + p.fee_stable;
+    let fwd = s.minter.preview_mint(&s.stable.address, &gross, &0);
+    assert!(fwd.gbt_out_gm >= 12_345);
+    assert!(fwd.net_stable >= p.net_stable);
+}
+
+#[test]
+fn mint_exact_out_delivers_exact_amount() {
+    let s = setup();
+    let p = s.minter.preview_mint_exact_out(&s.stable.address, &5_000, &0);
+    let gross = p.net_stable + p.fee_stable;
+    let before = s.stable.balance(&s.payer);
+
+    let r = s.minter.mint_exact_out(&s.payer, &s.stable.address, &5_000, &gross, &0);
+    assert_eq!(r.gbt_out_gm, 5_000);
+    assert_eq!(r.stable_charged, gross);
+    assert_eq!(before - s.stable.balance(&s.payer), gross);
+    // 5 g con 7 decimales
+    assert_eq!(s.gbt.balance(&s.payer), 50_000_000);
+}
+
+#[test]
+#[should_panic(expected = "max_stable_in exceeded")]
+fn mint_exact_out_enforces_max_in() {
+    let s = setup();
+    let p = s.minter.preview_mint_exact_out(&s.stable.address, &5_000, &0);
+    s.minter.mint_exact_out(&s.payer, &s.stable.address, &5_000, &(p.net_stable + p.fee_stable - 1), &0);
+}
+
+#[test]
+#[should_panic(expected = "bad fee")]
+fn global_fee_must_be_below_bps() {
+    let s = setup();
+    s.minter.set_fee_bps(&10_000);
+}
+
+#[test]
+fn receipts_are_indexed_per_payer() {
+    let s = setup();
+    let other = Address::generate(&s.env);
+    let mut seqs = std::vec::Vec::new();
+    for i in 0..60 {
+        let r = if i % 3 == 0 {
+            s.minter.mint_to(&s.payer, &other, &s.stable.address, &2_000_000_000, &0)
+        } else {
+            s.minter.mint(&s.payer, &s.stable.address, &2_000_000_000, &0)
+        };
+        seqs.push(r.seq);
+    }
+
+    assert_eq!(s.minter.receipt_count(&s.payer), 60);
+    assert_eq!(s.minter.receipt_count(&other), 0);
+    // Páginas de a lo sumo 50
+    let first = s.minter.receipts_of(&s.payer, &0, &100);
+    assert_eq!(first.len(), 50);
+    let rest = s.minter.receipts_of(&s.payer, &50, &50);
+    assert_eq!(rest.len(), 10);
+    let all: std::vec::Vec<u64> = first.iter().chain(rest.iter()).collect();
+    assert_eq!(all, seqs);
+    assert!(s.minter.receipts_of(&s.payer, &60, &10).is_empty());
+    assert_eq!(s.minter.get_receipt(&seqs[59]).unwrap().payer, s.payer);
+}
+
+// Mina 0 con 14 g de capacidad (25 g probadas * 0.70 * 80%) y un minteo previo de
+// 1.96 g: quedan 12.04 g.
+fn small_capacity(s: &Setup) -> i128 {
+    s.minter.upsert_mine(&0, &0, &0, &0, &0, &25_000, &true);
+    s.minter.mint(&s.payer, &s.stable.address, &2_000_000_000, &0);
+    s.minter.available_capacity_gm(&0)
+}
+
+#[test]
+fn batch_over_capacity_reverts_whole() {
+    let s = setup();
+    let available = small_capacity(&s);
+    assert_eq!(available, 12_040);
+    let (alice, bob) = (Address::generate(&s.env), Address::generate(&s.env));
+
+    // 8 USD*100 con fee 2% rinden 7.84 g: cada tramo cabe solo, los dos juntos no
+    let leg = s.minter.preview_mint(&s.stable.address, &8_000_000_000, &0);
+    assert_eq!(leg.gbt_out_gm, 7_840);
+    let stable_before = s.stable.balance(&s.payer);
+    let mine_before = s.minter.mine_minted_gm(&0);
+
+    let mut legs = Vec::new(&s.env);
+    legs.push_back((alice.clone(), 8_000_000_000i128));
+    legs.push_back((bob.clone(), 8_000_000_000i128));
+    assert!(s.minter.try_mint_batch(&s.payer, &s.stable.address, &legs, &0).is_err());
+
+    assert_eq!(s.stable.balance(&s.payer), stable_before);
+    assert_eq!(s.gbt.balance(&alice), 0);
+    assert_eq!(s.gbt.balance(&bob), 0);
+    assert_eq!(s.minter.available_capacity_gm(&0), available);
+    assert_eq!(s.minter.mine_minted_gm(&0), mine_before);
+    assert_eq!(s.minter.receipt_count(&s.payer), 1);
+
+    // Un solo tramo sí entra
+    legs.pop_back();
+    s.minter.mint_batch(&s.payer, &s.stable.address, &legs, &0);
+    assert_eq!(s.minter.available_capacity_gm(&0), available - 7_840);
+}
+
+#[test]
+fn batch_with_leg_below_minimum_reverts_whole() {
+    let s = setup();
+    let available = small_capacity(&s);
+    let (alice, bob) = (Address::generate(&s.env), Address::generate(&s.env));
+    let stable_before = s.stable.balance(&s.payer);
+
+    // El segundo tramo rinde 0.49 g, por debajo del mínimo de 1 g
+    let mut legs = Vec::new(&s.env);
+    legs.push_back((alice.clone(), 2_000_000_000i128));
+    legs.push_back((bob.clone(), 500_000_000i128));
+    assert!(s.minter.try_mint_batch(&s.payer, &s.stable.address, &legs, &0).is_err());
+
+    assert_eq!(s.stable.balance(&s.payer), stable_before);
+    assert_eq!(s.gbt.balance(&alice), 0);
+    assert_eq!(s.minter.available_capacity_gm(&0), available);
+    assert_eq!(s.minter.mine_minted_gm(&0), 1_960);
+}
+
+#[test]
+#[should_panic(expected = "below minimum or zero")]
+fn batch_rejects_leg_below_minimum() {
+    let s = setup();
+    let mut legs = Vec::new(&s.env);
+    legs.push_back((Address::generate(&s.env), 2_000_000_000i128));
+    legs.push_back((Address::generate(&s.env), 500_000_000i128));
+    s.minter.mint_batch(&s.payer, &s.stable.address, &legs, &0);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(200))]
+
+    // Pagar lo que cotiza `preview_mint_exact_out` siempre rinde al menos lo pedido,
+    // sea cual sea la combinación de decimales, FX, precio y fee.
+    #[test]
+    fn exact_out_gross_yields_at_least_requested(
+        decimals in 0u32..=18,
+        fx_1e7 in prop_oneof![Just(USD_1E7), 100_000i128..=1_000_000_000],
+        price_1e7 in 10_000_000i128..=100_000_000_000,
+        fee_bps in 0u32..BPS as u32,
+        gbt_out_gm in 1_000i128..=1_000_000_000,
+    ) {
+        let s = setup();
+        s.oracle.set_price(&price_1e7);
+        let token = pay_token(&s, decimals, fx_1e7, Some(fee_bps));
+
+        let p = s.minter.preview_mint_exact_out(&token, &gbt_out_gm, &0);
+        prop_assert_eq!(p.gbt_out_gm, gbt_out_gm);
+        let gross = p.net_stable + p.fee_stable;
+
+        let fwd = s.minter.preview_mint(&token, &gross, &0);
+        prop_assert!(fwd.gbt_out_gm >= gbt_out_gm);
+        prop_assert!(fwd.net_stable >= p.net_stable);
+    }
+}
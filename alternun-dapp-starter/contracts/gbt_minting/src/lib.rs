@@ -4,7 +4,6 @@ use soroban_sdk::{
     contract, contractimpl,
     Env, Address, Symbol,
     symbol_short,
-    token::Client as TokenClient,
 };
 
 pub struct State;
@@ -14,12 +13,14 @@ impl State {
     pub const TOKEN_STABLE: Symbol = symbol_short!("stc");
     pub const TREASURY: Symbol     = symbol_short!("tres");
     pub const ORACLE: Symbol       = symbol_short!("orcl");
+    pub const MINT_SEQ: Symbol     = symbol_short!("mint_seq"); // referencia de pago para Treasury.route
 }
 
 #[contract]
 pub struct GbtMinting;
 
 // ---- Clientes de contratos externos ----
+#[allow(dead_code)]
 mod oracle {
     use soroban_sdk::{Env, contractclient};
 
@@ -29,16 +30,18 @@ mod oracle {
     }
 }
 
+#[allow(dead_code)]
 mod treasury {
-    use soroban_sdk::{Env, Address, contractclient};
+    use soroban_sdk::{Env, Address, Vec, contractclient};
 
     #[contractclient(name = "TreasuryClient")]
     pub trait Treasury {
-        fn route(e: Env, token: Address, from: Address, amount: i128);
+        fn route(e: Env, source: Address, token: Address, from: Address, amount: i128, reference: u64) -> Vec<(Address, i128)>;
     }
 }
 
 // Cliente explícito del contrato GBT con método `mint` (admin)
+#[allow(dead_code)]
 mod gbt_token {
     use soroban_sdk::{Env, Address, contractclient};
 
@@ -86,8 +89,10 @@ impl GbtMinting {
 
         // 3) Split 50/30/20 directamente desde el payer (requiere su firma)
         payer.require_auth();
+        let seq: u64 = e.storage().instance().get(&State::MINT_SEQ).unwrap_or(0) + 1;
+        e.storage().instance().set(&State::MINT_SEQ, &seq);
         let tres = crate::treasury::TreasuryClient::new(&e, &treasury);
-        tres.route(&token_stable, &payer, &amount_stable);
+        tres.route(&e.current_contract_address(), &token_stable, &payer, &amount_stable, &seq);
 
         // 4) Mintear GBT al payer (este contrato es admin del token GBT)
         let gbt = crate::gbt_token::GbtClient::new(&e, &token_gbt);
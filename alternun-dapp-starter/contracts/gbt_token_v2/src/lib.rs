@@ -1,6 +1,6 @@
 ﻿#![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, symbol_short};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
 
 #[contracttype]
 enum Key {
@@ -42,6 +42,10 @@ impl GbtTokenV2 {
         e.storage().instance().set(&Key::Admin, &new_admin);
     }
 
+    pub fn decimals(e: Env) -> u32 {
+        e.storage().instance().get::<_, i128>(&Key::Decimals).unwrap() as u32
+    }
+
     pub fn balance(e: Env, owner: Address) -> i128 {
         get_balance(&e, &owner)
     }
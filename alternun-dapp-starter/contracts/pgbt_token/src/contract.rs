@@ -0,0 +1,138 @@
+//! Implementación SEP-41 del recibo pGBT (misma estructura que el `token` de
+//! soroban-examples). Los eventos usan los mismos topics que `soroban-token-sdk`.
+use crate::admin::{has_administrator, read_administrator, write_administrator};
+use crate::allowance::{read_allowance, spend_allowance, write_allowance};
+use crate::balance::{read_balance, receive_balance, spend_balance};
+use crate::metadata::{read_decimal, read_name, read_symbol, write_metadata};
+use crate::storage_types::{TokenMetadata, INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::token::{self, Interface as _};
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, String};
+
+fn check_nonnegative_amount(amount: i128) {
+    if amount < 0 {
+        panic!("negative amount is not allowed: {}", amount)
+    }
+}
+
+fn bump_instance(e: &Env) {
+    e.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+#[contract]
+pub struct PgbtToken;
+
+#[contractimpl]
+impl PgbtToken {
+    /// `admin` debe ser el `project_vault` que emite los recibos. No pide firma:
+    /// hay que llamarlo apenas se despliega. Si alguien se adelanta con otro admin,
+    /// `set_receipt_token` del vault rechaza el recibo y basta con redesplegarlo.
+    pub fn init(e: Env, admin: Address, decimal: u32, name: String, symbol: String) {
+        if has_administrator(&e) {
+            panic!("already initialized");
+        }
+        if decimal > 18 {
+            panic!("Decimal must not be greater than 18");
+        }
+        write_administrator(&e, &admin);
+        write_metadata(&e, TokenMetadata { decimal, name, symbol });
+    }
+
+    pub fn mint(e: Env, to: Address, amount: i128) {
+        check_nonnegative_amount(amount);
+        let admin = read_administrator(&e);
+        admin.require_auth();
+        bump_instance(&e);
+
+        receive_balance(&e, to.clone(), amount);
+        e.events().publish((symbol_short!("mint"), admin, to), amount);
+    }
+
+    pub fn set_admin(e: Env, new_admin: Address) {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+        bump_instance(&e);
+
+        write_administrator(&e, &new_admin);
+        e.events().publish((symbol_short!("set_admin"), admin), new_admin);
+    }
+
+    pub fn admin(e: Env) -> Address {
+        read_administrator(&e)
+    }
+}
+
+#[contractimpl]
+impl token::Interface for PgbtToken {
+    fn allowance(e: Env, from: Address, spender: Address) -> i128 {
+        bump_instance(&e);
+        read_allowance(&e, from, spender).amount
+    }
+
+    fn approve(e: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        from.require_auth();
+        check_nonnegative_amount(amount);
+        bump_instance(&e);
+
+        write_allowance(&e, from.clone(), spender.clone(), amount, expiration_ledger);
+        e.events().publish((symbol_short!("approve"), from, spender), (amount, expiration_ledger));
+    }
+
+    fn balance(e: Env, id: Address) -> i128 {
+        bump_instance(&e);
+        read_balance(&e, id)
+    }
+
+    fn transfer(e: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        check_nonnegative_amount(amount);
+        bump_instance(&e);
+
+        spend_balance(&e, from.clone(), amount);
+        receive_balance(&e, to.clone(), amount);
+        e.events().publish((symbol_short!("transfer"), from, to), amount);
+    }
+
+    fn transfer_from(e: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+        check_nonnegative_amount(amount);
+        bump_instance(&e);
+
+        spend_allowance(&e, from.clone(), spender, amount);
+        spend_balance(&e, from.clone(), amount);
+        receive_balance(&e, to.clone(), amount);
+        e.events().publish((symbol_short!("transfer"), from, to), amount);
+    }
+
+    fn burn(e: Env, from: Address, amount: i128) {
+        from.require_auth();
+        check_nonnegative_amount(amount);
+        bump_instance(&e);
+
+        spend_balance(&e, from.clone(), amount);
+        e.events().publish((symbol_short!("burn"), from), amount);
+    }
+
+    fn burn_from(e: Env, spender: Address, from: Address, amount: i128) {
+        spender.require_auth();
+        check_nonnegative_amount(amount);
+        bump_instance(&e);
+
+        spend_allowance(&e, from.clone(), spender, amount);
+        spend_balance(&e, from.clone(), amount);
+        e.events().publish((symbol_short!("burn"), from), amount);
+    }
+
+    fn decimals(e: Env) -> u32 {
+        read_decimal(&e)
+    }
+
+    fn name(e: Env) -> String {
+        read_name(&e)
+    }
+
+    fn symbol(e: Env) -> String {
+        read_symbol(&e)
+    }
+}
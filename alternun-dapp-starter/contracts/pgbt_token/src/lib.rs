@@ -0,0 +1,20 @@
+#![no_std]
+
+//! pGBT: recibo SEP-41 de GBT bloqueado en `project_vault`.
+//!
+//! Basado en el `token` de soroban-examples (vendorizado en
+//! `gbt_minting/soroban-examples/token`), adaptado a soroban-sdk 21 sin
+//! `soroban-token-sdk`. El admin es el vault: sólo él mintea, al depositar. El
+//! recibo es transferible; quien lo tenga lo canjea por GBT contra una posición
+//! vencida con la firma de su dueño (`redeem` del vault), quemándolo.
+
+mod admin;
+mod allowance;
+mod balance;
+mod contract;
+mod metadata;
+mod storage_types;
+
+pub use crate::contract::{PgbtToken, PgbtTokenClient};
+
+mod test;
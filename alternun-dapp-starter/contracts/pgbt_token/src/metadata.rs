@@ -0,0 +1,23 @@
+use soroban_sdk::{Env, String};
+
+use crate::storage_types::{DataKey, TokenMetadata};
+
+fn read_metadata(e: &Env) -> TokenMetadata {
+    e.storage().instance().get(&DataKey::Metadata).unwrap()
+}
+
+pub fn read_decimal(e: &Env) -> u32 {
+    read_metadata(e).decimal
+}
+
+pub fn read_name(e: &Env) -> String {
+    read_metadata(e).name
+}
+
+pub fn read_symbol(e: &Env) -> String {
+    read_metadata(e).symbol
+}
+
+pub fn write_metadata(e: &Env, metadata: TokenMetadata) {
+    e.storage().instance().set(&DataKey::Metadata, &metadata);
+}
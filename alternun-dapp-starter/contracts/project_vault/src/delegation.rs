@@ -0,0 +1,168 @@
+//! Poder de voto delegable a partir de las posiciones del vault.
+//!
+//! Sólo cuentan las posiciones en el asset de voto (`set_voting_asset`), con su
+//! peso de tier. Cada posición vota por sí misma hasta que su dueño la delega a
+//! otra dirección. El poder de cada dirección se guarda en checkpoints por
+//! ledger, así un contrato de gobernanza puede consultar `voting_power_at` en el
+//! ledger de apertura de una propuesta.
+
+use soroban_sdk::{contractimpl, contracttype, symbol_short, Address, Env};
+
+use crate::rewards::weight_of;
+use crate::{read_position, require_admin, DataKey, ProjectVault, ProjectVaultClient, State};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub power: i128,
+}
+
+fn read_voting_asset(e: &Env) -> Option<Address> {
+    e.storage().instance().get(&State::VOTE_ASSET)
+}
+
+fn read_delegate(e: &Env, owner: &Address, token: &Address) -> Address {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Delegate(owner.clone(), token.clone()))
+        .unwrap_or(owner.clone())
+}
+
+// Serie de checkpoints: la de una dirección o la del total (`None`). Cada
+// checkpoint va en su propia clave, indexada desde 0, con el largo aparte.
+fn count_key(who: Option<&Address>) -> DataKey {
+    match who {
+        Some(addr) => DataKey::CheckpointCount(addr.clone()),
+        None => DataKey::TotalCheckpointCount,
+    }
+}
+
+fn checkpoint_key(who: Option<&Address>, index: u32) -> DataKey {
+    match who {
+        Some(addr) => DataKey::Checkpoint(addr.clone(), index),
+        None => DataKey::TotalCheckpoint(index),
+    }
+}
+
+fn read_count(e: &Env, who: Option<&Address>) -> u32 {
+    e.storage().persistent().get(&count_key(who)).unwrap_or(0)
+}
+
+fn read_checkpoint(e: &Env, who: Option<&Address>, index: u32) -> Checkpoint {
+    e.storage().persistent().get(&checkpoint_key(who, index)).unwrap()
+}
+
+// Último valor con `ledger <= at` (búsqueda binaria sobre las claves; los
+// checkpoints están ordenados).
+fn power_at(e: &Env, who: Option<&Address>, at: u32) -> i128 {
+    let (mut lo, mut hi) = (0u32, read_count(e, who));
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if read_checkpoint(e, who, mid).ledger <= at {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    if lo == 0 { 0 } else { read_checkpoint(e, who, lo - 1).power }
+}
+
+// Suma `delta` al último valor y lo registra en el ledger actual (pisando el
+// checkpoint si ya hay uno en este ledger).
+fn push_checkpoint(e: &Env, who: Option<&Address>, delta: i128) {
+    if delta == 0 {
+        return;
+    }
+    let count = read_count(e, who);
+    let ledger = e.ledger().sequence();
+    let last = if count == 0 { None } else { Some(read_checkpoint(e, who, count - 1)) };
+    let power = last.as_ref().map(|c| c.power).unwrap_or(0) + delta;
+    match last {
+        Some(c) if c.ledger == ledger => {
+            e.storage().persistent().set(&checkpoint_key(who, count - 1), &Checkpoint { ledger, power });
+        }
+        _ => {
+            e.storage().persistent().set(&checkpoint_key(who, count), &Checkpoint { ledger, power });
+            e.storage().persistent().set(&count_key(who), &(count + 1));
+        }
+    }
+}
+
+fn move_power(e: &Env, from: &Address, to: &Address, amount: i128) {
+    push_checkpoint(e, Some(from), -amount);
+    push_checkpoint(e, Some(to), amount);
+}
+
+/// Se llama al cambiar el peso de la posición de `owner` en `token`.
+pub(crate) fn on_weight_change(e: &Env, owner: &Address, token: &Address, old_weight: i128, new_weight: i128) {
+    if read_voting_asset(e).as_ref() != Some(token) || old_weight == new_weight {
+        return;
+    }
+    let delegate = read_delegate(e, owner, token);
+    push_checkpoint(e, Some(&delegate), new_weight - old_weight);
+    push_checkpoint(e, None, new_weight - old_weight);
+}
+
+#[contractimpl]
+impl ProjectVault {
+    /// Define el asset cuyas posiciones dan poder de voto (solo admin). Sólo se
+    /// puede fijar una vez y antes de que haya posiciones, para no romper los
+    /// checkpoints.
+    pub fn set_voting_asset(e: Env, admin: Address, token: Address) {
+        require_admin(&e, &admin);
+        if read_voting_asset(&e).is_some() {
+            panic!("voting asset already set");
+        }
+        if e.storage().persistent().get::<_, i128>(&DataKey::TotalWeight(token.clone())).unwrap_or(0) != 0 {
+            panic!("asset already has positions");
+        }
+        e.storage().instance().set(&State::VOTE_ASSET, &token);
+    }
+
+    pub fn voting_asset(e: Env) -> Option<Address> {
+        read_voting_asset(&e)
+    }
+
+    /// Delega el poder de voto de la posición de `owner` en `token` a `to`
+    /// (`to == owner` la vuelve a votar por sí misma). Rige también para los
+    /// depósitos futuros de esa posición.
+    pub fn delegate(e: Env, owner: Address, token: Address, to: Address) {
+        owner.require_auth();
+        let prev = read_delegate(&e, &owner, &token);
+        if prev == to {
+            return;
+        }
+        let key = DataKey::Delegate(owner.clone(), token.clone());
+        if to == owner {
+            e.storage().persistent().remove(&key);
+        } else {
+            e.storage().persistent().set(&key, &to);
+        }
+
+        if read_voting_asset(&e).as_ref() == Some(&token) {
+            let weight = read_position(&e, &owner, &token).map(|p| weight_of(&p)).unwrap_or(0);
+            move_power(&e, &prev, &to, weight);
+        }
+        e.events().publish((symbol_short!("delegate"), owner, token), (prev, to));
+    }
+
+    pub fn delegate_of(e: Env, owner: Address, token: Address) -> Address {
+        read_delegate(&e, &owner, &token)
+    }
+
+    /// Poder de voto actual de `addr` (propio no delegado más lo delegado a él).
+    pub fn voting_power(e: Env, addr: Address) -> i128 {
+        power_at(&e, Some(&addr), e.ledger().sequence())
+    }
+
+    /// Poder de voto de `addr` al cierre del ledger `ledger`.
+    pub fn voting_power_at(e: Env, addr: Address, ledger: u32) -> i128 {
+        power_at(&e, Some(&addr), ledger)
+    }
+
+    /// Poder de voto total al cierre del ledger `ledger`.
+    pub fn total_voting_power_at(e: Env, ledger: u32) -> i128 {
+        power_at(&e, None, ledger)
+    }
+}
@@ -1,8 +1,9 @@
 #![no_std]
 
+use alternun_math::BPS;
 use soroban_sdk::{
-    contract, contractimpl,
-    Env, Address, Symbol,
+    contract, contractimpl, contracttype,
+    Env, Address, Symbol, Vec,
     symbol_short,
     token::Client as TokenClient,
 };
@@ -11,7 +12,138 @@ pub struct State;
 impl State {
     pub const INIT: Symbol = symbol_short!("init");
     pub const ADMIN: Symbol = symbol_short!("admin");
-    pub const TOTAL: Symbol = symbol_short!("total");
+    pub const ASSETS: Symbol = symbol_short!("assets"); // Vec<Address> tokens aceptados
+    pub const TIERS: Symbol = symbol_short!("tiers");   // Vec<Tier>
+    pub const PENALTY: Symbol = symbol_short!("penalty"); // u32 bps por salida anticipada
+    pub const RECOVERY: Symbol = symbol_short!("recovery"); // pool Recovery del treasury
+    pub const VERIFIERS: Symbol = symbol_short!("verifiers"); // Verifiers de hitos
+    pub const VOTE_ASSET: Symbol = symbol_short!("vote_ast"); // asset que da poder de voto
+}
+
+// Claves compuestas (persistent)
+#[contracttype]
+pub enum DataKey {
+    Position(Address, Address), // (owner, token) -> Position
+    Total(Address),             // token -> total bloqueado
+    Receipt(Address),           // token -> contrato del recibo (pGBT para GBT)
+    TotalWeight(Address),       // token -> suma de pesos para rewards
+    RewardTokens(Address),      // token bloqueado -> Vec<Address> rewards habilitados
+    RewardStream(Address, Address), // (stake, reward) -> RewardStream
+    UserReward(Address, Address, Address), // (owner, stake, reward) -> UserReward
+    ProjectSeq,                 // último id de proyecto
+    Project(u32),               // id -> Project
+    Contribution(u32, Address), // (proyecto, aportante) -> monto
+    Milestones(u32),            // proyecto -> Vec<Milestone>
+    Delegate(Address, Address), // (owner, token) -> delegado del poder de voto
+    CheckpointCount(Address),   // dirección -> cantidad de checkpoints de poder de voto
+    Checkpoint(Address, u32),   // (dirección, índice) -> Checkpoint
+    TotalCheckpointCount,       // cantidad de checkpoints del total
+    TotalCheckpoint(u32),       // índice -> Checkpoint del total
+}
+
+// --- Cliente del recibo (el vault es su admin, así puede mintear) ---
+#[allow(dead_code)]
+mod receipt {
+    use soroban_sdk::{Env, Address, contractclient};
+    #[contractclient(name = "ReceiptClient")]
+    pub trait Receipt {
+        fn mint(e: Env, to: Address, amount: i128);
+        fn admin(e: Env) -> Address;
+    }
+}
+
+/// Depósito de un usuario en un token.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Position {
+    pub amount: i128,
+    pub deposited_at: u64,    // timestamp del primer depósito
+    pub lock_until: u64,      // timestamp desde el que se puede retirar sin penalidad
+    pub tier: u32,            // índice en `tiers()`
+    pub multiplier_bps: u32,  // multiplicador del tier (10_000 = 1x); 1x al vencer
+}
+
+fn require_admin(e: &Env, admin: &Address) {
+    let stored_admin: Address = e.storage().instance().get(&State::ADMIN).unwrap();
+    if *admin != stored_admin {
+        panic!("not admin");
+    }
+    admin.require_auth();
+}
+
+fn read_assets(e: &Env) -> Vec<Address> {
+    e.storage().instance().get(&State::ASSETS).unwrap_or(Vec::new(e))
+}
+
+fn read_receipt(e: &Env, token: &Address) -> Option<Address> {
+    e.storage().persistent().get(&DataKey::Receipt(token.clone()))
+}
+
+fn read_position(e: &Env, owner: &Address, token: &Address) -> Option<Position> {
+    e.storage().persistent().get(&DataKey::Position(owner.clone(), token.clone()))
+}
+
+// Toda escritura de posición pasa por acá para liquidar rewards con el peso previo
+// y actualizar el poder de voto.
+fn write_position(e: &Env, owner: &Address, token: &Address, p: &Position) {
+    let old_weight = read_position(e, owner, token).map(|o| rewards::weight_of(&o)).unwrap_or(0);
+    let new_weight = rewards::weight_of(p);
+    rewards::on_weight_change(e, owner, token, old_weight, new_weight);
+    delegation::on_weight_change(e, owner, token, old_weight, new_weight);
+
+    let key = DataKey::Position(owner.clone(), token.clone());
+    if p.amount == 0 {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, p);
+    }
+}
+
+fn read_total(e: &Env, token: &Address) -> i128 {
+    e.storage().persistent().get(&DataKey::Total(token.clone())).unwrap_or(0)
+}
+
+fn add_total(e: &Env, token: &Address, delta: i128) {
+    let total = read_total(e, token) + delta;
+    e.storage().persistent().set(&DataKey::Total(token.clone()), &total);
+}
+
+// Descuenta `amount` de la posición y del total, y quema los recibos de `holder`
+// (el dueño, salvo en `redeem`). Quien llama transfiere los fondos y verifica
+// `surplus`.
+fn debit_position(e: &Env, owner: &Address, token: &Address, amount: i128, holder: &Address) -> Position {
+    if amount <= 0 {
+        panic!("amount must be > 0");
+    }
+    let mut pos = match read_position(e, owner, token) {
+        Some(p) => p,
+        None => panic!("no position"),
+    };
+    if amount > pos.amount {
+        panic!("insufficient position");
+    }
+    pos.amount -= amount;
+    // Vencido el bloqueo, lo que queda pesa 1x (ver `poke`)
+    if e.ledger().timestamp() >= pos.lock_until {
+        pos.multiplier_bps = BPS as u32;
+    }
+    write_position(e, owner, token, &pos);
+    add_total(e, token, -amount);
+
+    if let Some(receipt) = read_receipt(e, token) {
+        TokenClient::new(e, &receipt).burn(holder, &amount);
+    }
+    pos
+}
+
+// Saldo real del vault en `token` menos lo contabilizado; nunca puede ser negativo.
+fn surplus(e: &Env, token: &Address) -> i128 {
+    let balance = TokenClient::new(e, token).balance(&e.current_contract_address());
+    let surplus = balance - read_total(e, token);
+    if surplus < 0 {
+        panic!("vault balance below total locked");
+    }
+    surplus
 }
 
 #[contract]
@@ -26,44 +158,161 @@ impl ProjectVault {
         admin.require_auth();
 
         e.storage().instance().set(&State::ADMIN, &admin);
-        e.storage().instance().set(&State::TOTAL, &0_i128);
         e.storage().instance().set(&State::INIT, &true);
     }
 
-    /// Deposita GBT en el vault (transferencia desde `from` hacia `to`).
-    pub fn deposit(e: Env, token_gbt: Address, from: Address, to: Address, amount: i128) {
-    // Asegura la autorización del 'from' en la llamada raíz
-    from.require_auth();
+    /// Acepta o deja de aceptar `token` para nuevos depósitos (solo admin). Las
+    /// posiciones existentes siguen pudiendo retirarse.
+    pub fn set_asset(e: Env, admin: Address, token: Address, allowed: bool) {
+        require_admin(&e, &admin);
+        let mut assets = read_assets(&e);
+        match (assets.first_index_of(&token), allowed) {
+            (None, true) => assets.push_back(token),
+            (Some(i), false) => {
+                assets.remove(i);
+            }
+            _ => return,
+        }
+        e.storage().instance().set(&State::ASSETS, &assets);
+    }
 
-    // Transferencia del usuario hacia el vault (o destino indicado)
-    let token_client = TokenClient::new(&e, &token_gbt);
-    token_client.transfer(&from, &to, &amount);
+    pub fn assets(e: Env) -> Vec<Address> {
+        read_assets(&e)
+    }
 
-    // Actualiza el total del vault
-    let total: i128 = e.storage().instance().get(&State::TOTAL).unwrap_or(0);
-    e.storage().instance().set(&State::TOTAL, &(total + amount));
-}
+    /// Define el token recibo de `token` (solo admin), p. ej. pGBT para GBT. El
+    /// vault tiene que ser admin del recibo: si no, no podría mintear y los
+    /// depósitos fallarían. Se puede cambiar sólo mientras el token no tenga nada
+    /// bloqueado, así los recibos ya emitidos siguen siendo canjeables.
+    pub fn set_receipt_token(e: Env, admin: Address, token: Address, receipt: Address) {
+        require_admin(&e, &admin);
+        if read_total(&e, &token) != 0 {
+            panic!("asset already has deposits");
+        }
+        if crate::receipt::ReceiptClient::new(&e, &receipt).admin() != e.current_contract_address() {
+            panic!("vault is not receipt admin");
+        }
+        e.storage().persistent().set(&DataKey::Receipt(token), &receipt);
+    }
+
+    pub fn receipt_token(e: Env, token: Address) -> Option<Address> {
+        read_receipt(&e, &token)
+    }
+
+    /// Deposita `token` en el propio vault y lo suma a la posición de `from` en ese
+    /// token, bloqueada según `tier` desde ahora. Un nuevo depósito puede subir de
+    /// tier pero nunca bajar ni acortar el bloqueo vigente. Si el token tiene
+    /// recibo, mintea `amount` 1:1 a `from`.
+    pub fn deposit(e: Env, token: Address, from: Address, amount: i128, tier: u32) {
+        // Asegura la autorización del 'from' en la llamada raíz
+        from.require_auth();
+        if amount <= 0 {
+            panic!("amount must be > 0");
+        }
+        if !read_assets(&e).contains(&token) {
+            panic!("asset not allowed");
+        }
+
+        let t = match tiers::read_tiers(&e).get(tier) {
+            Some(t) => t,
+            None => panic!("unknown tier"),
+        };
+
+        let now = e.ledger().timestamp();
+        let mut pos = read_position(&e, &from, &token).unwrap_or(Position {
+            amount: 0,
+            deposited_at: now,
+            lock_until: 0,
+            tier,
+            multiplier_bps: t.multiplier_bps,
+        });
+        if tier < pos.tier {
+            panic!("cannot lower tier");
+        }
+        pos.amount += amount;
+        pos.lock_until = pos.lock_until.max(now + t.lock_secs);
+        pos.tier = tier;
+        pos.multiplier_bps = t.multiplier_bps;
+        write_position(&e, &from, &token, &pos);
+
+        // Transferencia del usuario hacia el vault
+        let token_client = TokenClient::new(&e, &token);
+        token_client.transfer(&from, &e.current_contract_address(), &amount);
 
+        // Actualiza el total del token y verifica contra el saldo real
+        add_total(&e, &token, amount);
+        surplus(&e, &token);
 
-    /// Devuelve el total bloqueado en el vault.
-    pub fn total_locked(e: Env) -> i128 {
-        e.storage().instance().get(&State::TOTAL).unwrap_or(0)
+        if let Some(receipt) = read_receipt(&e, &token) {
+            crate::receipt::ReceiptClient::new(&e, &receipt).mint(&from, &amount);
+        }
     }
-pub fn withdraw(e: Env, admin: Address, token_gbt: Address, to: Address, amount: i128) {
-    // Solo el admin del vault puede retirar
-    let stored_admin: Address = e.storage().instance().get(&State::ADMIN).unwrap();
-    if admin != stored_admin {
-        panic!("not admin");
+
+    /// Devuelve el total bloqueado en el vault para `token` (posiciones y aportes a
+    /// proyectos).
+    pub fn total_locked(e: Env, token: Address) -> i128 {
+        read_total(&e, &token)
     }
-    admin.require_auth();
 
-    // Transferir desde el contrato (vault) hacia 'to'
-    let token = TokenClient::new(&e, &token_gbt);
-    token.transfer(&e.current_contract_address(), &to, &amount);
+    /// Saldo real del vault en `token` por encima de `total_locked` (p. ej.
+    /// transferencias directas). Falla si el saldo no cubre lo contabilizado.
+    pub fn reconcile(e: Env, token: Address) -> i128 {
+        surplus(&e, &token)
+    }
+
+    /// Posición de `owner` en `token`, si tiene saldo depositado.
+    pub fn position(e: Env, owner: Address, token: Address) -> Option<Position> {
+        read_position(&e, &owner, &token)
+    }
 
-    // Actualizar el total bloqueado
-    let total: i128 = e.storage().instance().get(&State::TOTAL).unwrap_or(0);
-    e.storage().instance().set(&State::TOTAL, &(total - amount));
+    /// Retira `amount` de la posición propia en `token` una vez vencido el bloqueo.
+    /// Si el token tiene recibo, quema `amount` de recibos del dueño: si los
+    /// transfirió, tiene que recuperarlos antes de retirar o autorizar que el
+    /// tenedor los canjee con `redeem`. Antes del vencimiento ver `withdraw_early`.
+    pub fn withdraw(e: Env, owner: Address, token: Address, amount: i128) {
+        owner.require_auth();
+        redeem_matured(&e, &owner, &owner, &token, amount);
+    }
+
+    /// Canjea `amount` de recibos de `holder` por `token` contra la posición
+    /// vencida de `owner`, que pierde ese monto (y su peso de rewards y votos) como
+    /// en un retiro. Firman los dos: el recibo es transferible, pero sólo el dueño
+    /// decide sobre su posición (p. ej. al vender la posición junto con los
+    /// recibos). Sólo para tokens con recibo.
+    pub fn redeem(e: Env, holder: Address, owner: Address, token: Address, amount: i128) {
+        holder.require_auth();
+        owner.require_auth();
+        if read_receipt(&e, &token).is_none() {
+            panic!("no receipt token");
+        }
+        redeem_matured(&e, &holder, &owner, &token, amount);
+    }
 }
 
+// Debita la posición vencida de `owner`, quema recibos de `holder` y le paga.
+fn redeem_matured(e: &Env, holder: &Address, owner: &Address, token: &Address, amount: i128) {
+    let lock_until = read_position(e, owner, token).map(|p| p.lock_until).unwrap_or(0);
+    if e.ledger().timestamp() < lock_until {
+        panic!("position locked");
+    }
+    debit_position(e, owner, token, amount, holder);
+
+    // Transferir desde el contrato (vault) hacia quien entrega los recibos
+    let token_client = TokenClient::new(e, token);
+    token_client.transfer(&e.current_contract_address(), holder, &amount);
+    surplus(e, token);
 }
+
+mod delegation;
+mod milestones;
+mod projects;
+mod rewards;
+mod tiers;
+
+pub use delegation::Checkpoint;
+pub use milestones::{Milestone, Verifiers};
+pub use projects::{Project, ProjectStatus};
+pub use rewards::{RewardStream, UserReward};
+pub use tiers::{Maturity, Tier};
+
+mod test;
@@ -0,0 +1,207 @@
+//! Liberación de fondos de proyectos por hitos con firma de verificadores.
+//!
+//! Mientras el proyecto no recibió aportes, su beneficiario declara los hitos con
+//! el porcentaje (bps) que libera cada uno. Con el proyecto `Funded`, el
+//! beneficiario presenta el hash de la evidencia de un hito y los verificadores lo
+//! aprueban sobre ese hash; alcanzado el umbral, la porción del hito queda
+//! retirable por el beneficiario.
+
+use alternun_math::{mul_div, Rounding, BPS};
+use soroban_sdk::{
+    contractimpl, contracttype, symbol_short,
+    token::Client as TokenClient,
+    Address, BytesN, Env, Vec,
+};
+
+use crate::projects::{read_project, settle_status, write_project, ProjectStatus};
+use crate::{add_total, require_admin, surplus, DataKey, ProjectVault, ProjectVaultClient, State};
+
+const MAX_MILESTONES: u32 = 10;
+const MAX_VERIFIERS: u32 = 10;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Milestone {
+    pub release_bps: u32,
+    pub evidence: BytesN<32>,         // hash presentado por el beneficiario
+    pub submitted: bool,              // false = todavía sin evidencia
+    pub approvals: Vec<Address>,      // verificadores que aprobaron `evidence`
+    pub approved: bool,
+}
+
+/// Verificadores y aprobaciones necesarias por hito.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Verifiers {
+    pub members: Vec<Address>,
+    pub threshold: u32,
+}
+
+fn read_milestones(e: &Env, id: u32) -> Vec<Milestone> {
+    e.storage().persistent().get(&DataKey::Milestones(id)).unwrap_or(Vec::new(e))
+}
+
+fn write_milestones(e: &Env, id: u32, ms: &Vec<Milestone>) {
+    e.storage().persistent().set(&DataKey::Milestones(id), ms);
+}
+
+fn read_verifiers(e: &Env) -> Option<Verifiers> {
+    e.storage().instance().get(&State::VERIFIERS)
+}
+
+fn milestone_at(ms: &Vec<Milestone>, index: u32) -> Milestone {
+    match ms.get(index) {
+        Some(m) => m,
+        None => panic!("milestone not found"),
+    }
+}
+
+#[contractimpl]
+impl ProjectVault {
+    /// Define los verificadores de hitos y cuántas aprobaciones hacen falta (solo admin).
+    pub fn set_verifiers(e: Env, admin: Address, members: Vec<Address>, threshold: u32) {
+        require_admin(&e, &admin);
+        if members.is_empty() || members.len() > MAX_VERIFIERS || threshold == 0 || threshold > members.len() {
+            panic!("invalid verifiers");
+        }
+        for (i, m) in members.iter().enumerate() {
+            if members.iter().skip(i + 1).any(|o| o == m) {
+                panic!("invalid verifiers");
+            }
+        }
+        e.storage().instance().set(&State::VERIFIERS, &Verifiers { members, threshold });
+    }
+
+    pub fn verifiers(e: Env) -> Option<Verifiers> {
+        read_verifiers(&e)
+    }
+
+    /// El beneficiario declara los hitos del proyecto `id` como porcentajes en bps
+    /// que suman 10_000. Sólo antes del primer aporte.
+    pub fn declare_milestones(e: Env, beneficiary: Address, id: u32, release_bps: Vec<u32>) {
+        let p = read_project(&e, id);
+        if beneficiary != p.beneficiary {
+            panic!("not beneficiary");
+        }
+        beneficiary.require_auth();
+        if p.status != ProjectStatus::Funding || p.raised != 0 {
+            panic!("project already funded");
+        }
+        if release_bps.is_empty() || release_bps.len() > MAX_MILESTONES {
+            panic!("invalid milestones");
+        }
+        let mut total: i128 = 0;
+        let mut ms = Vec::new(&e);
+        for bps in release_bps.iter() {
+            if bps == 0 {
+                panic!("invalid milestones");
+            }
+            total += bps as i128;
+            ms.push_back(Milestone {
+                release_bps: bps,
+                evidence: BytesN::from_array(&e, &[0; 32]),
+                submitted: false,
+                approvals: Vec::new(&e),
+                approved: false,
+            });
+        }
+        if total != BPS {
+            panic!("milestones must sum to 10000 bps");
+        }
+        write_milestones(&e, id, &ms);
+    }
+
+    pub fn milestones(e: Env, id: u32) -> Vec<Milestone> {
+        read_milestones(&e, id)
+    }
+
+    /// El beneficiario presenta (o reemplaza) la evidencia del hito `index`.
+    /// Reemplazarla descarta las aprobaciones previas.
+    pub fn submit_evidence(e: Env, beneficiary: Address, id: u32, index: u32, evidence: BytesN<32>) {
+        let mut p = read_project(&e, id);
+        if beneficiary != p.beneficiary {
+            panic!("not beneficiary");
+        }
+        beneficiary.require_auth();
+        settle_status(&e, &mut p);
+        if p.status != ProjectStatus::Funded {
+            panic!("project not funded");
+        }
+
+        let mut ms = read_milestones(&e, id);
+        let mut m = milestone_at(&ms, index);
+        if m.approved {
+            panic!("milestone already approved");
+        }
+        m.evidence = evidence.clone();
+        m.submitted = true;
+        m.approvals = Vec::new(&e);
+        ms.set(index, m);
+        write_milestones(&e, id, &ms);
+        e.events().publish((symbol_short!("evidence"), id, index), evidence);
+    }
+
+    /// Un verificador aprueba la evidencia `evidence` del hito `index`. Al alcanzar el
+    /// umbral, la porción del hito pasa a ser retirable por el beneficiario.
+    pub fn approve_milestone(e: Env, verifier: Address, id: u32, index: u32, evidence: BytesN<32>) {
+        let cfg = match read_verifiers(&e) {
+            Some(cfg) => cfg,
+            None => panic!("not verifier"),
+        };
+        if !cfg.members.contains(&verifier) {
+            panic!("not verifier");
+        }
+        verifier.require_auth();
+
+        let mut ms = read_milestones(&e, id);
+        let mut m = milestone_at(&ms, index);
+        if m.approved {
+            panic!("milestone already approved");
+        }
+        if !m.submitted || m.evidence != evidence {
+            panic!("evidence mismatch");
+        }
+        if m.approvals.contains(&verifier) {
+            panic!("already approved");
+        }
+        m.approvals.push_back(verifier);
+
+        let valid = m.approvals.iter().filter(|a| cfg.members.contains(a)).count() as u32;
+        if valid >= cfg.threshold {
+            m.approved = true;
+        }
+        let approved = m.approved;
+        ms.set(index, m);
+        write_milestones(&e, id, &ms);
+
+        if approved {
+            // Se recalcula sobre el total aprobado para que el último hito libere todo
+            let mut p = read_project(&e, id);
+            let approved_bps: i128 = ms.iter().filter(|m| m.approved).map(|m| m.release_bps as i128).sum();
+            p.released = mul_div(p.raised, approved_bps, BPS, Rounding::Floor).expect("math overflow");
+            write_project(&e, &p);
+            e.events().publish((symbol_short!("milestone"), id, index), p.released);
+        }
+    }
+
+    /// Transfiere al beneficiario lo liberado por hitos y aún no retirado.
+    pub fn withdraw_released(e: Env, beneficiary: Address, id: u32) -> i128 {
+        let mut p = read_project(&e, id);
+        if beneficiary != p.beneficiary {
+            panic!("not beneficiary");
+        }
+        beneficiary.require_auth();
+
+        let amount = p.released - p.withdrawn;
+        if amount <= 0 {
+            panic!("nothing released");
+        }
+        p.withdrawn += amount;
+        write_project(&e, &p);
+        add_total(&e, &p.token, -amount);
+
+        TokenClient::new(&e, &p.token).transfer(&e.current_contract_address(), &beneficiary, &amount);
+        surplus(&e, &p.token);
+        amount
+    }
+}
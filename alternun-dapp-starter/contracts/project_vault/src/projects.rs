@@ -0,0 +1,186 @@
+//! Registro de proyectos con meta de financiamiento.
+//!
+//! El admin registra cada proyecto con su beneficiario, hash de metadata, token,
+//! meta y fecha límite. Los aportes van dirigidos a un proyecto y quedan en el
+//! vault. Vencido el plazo el proyecto se cierra como `Funded` si alcanzó la meta
+//! o como `Failed`, y en ese caso cada aportante recupera lo suyo con `refund`.
+//! Si en cambio queda `Funded`, los fondos salen sólo por hitos (`milestones`).
+//! Los aportes no son posiciones: no tienen tier, rewards ni recibo.
+
+use soroban_sdk::{
+    contractimpl, contracttype, symbol_short,
+    token::Client as TokenClient,
+    Address, BytesN, Env,
+};
+
+use crate::{add_total, read_assets, require_admin, surplus, DataKey, ProjectVault, ProjectVaultClient};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProjectStatus {
+    Funding,
+    Funded,
+    Failed,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Project {
+    pub id: u32,
+    pub beneficiary: Address,
+    pub metadata_hash: BytesN<32>,
+    pub token: Address,
+    pub goal: i128,
+    pub deadline: u64, // timestamp del ledger; se aceptan aportes hasta antes de este momento
+    pub raised: i128,
+    pub status: ProjectStatus,
+    pub released: i128,  // liberado por hitos aprobados (ver `milestones`)
+    pub withdrawn: i128, // retirado por el beneficiario
+}
+
+pub(crate) fn read_project(e: &Env, id: u32) -> Project {
+    match e.storage().persistent().get(&DataKey::Project(id)) {
+        Some(p) => p,
+        None => panic!("project not found"),
+    }
+}
+
+pub(crate) fn write_project(e: &Env, p: &Project) {
+    e.storage().persistent().set(&DataKey::Project(p.id), p);
+}
+
+// Cierra el proyecto si ya venció el plazo y sigue en `Funding`.
+pub(crate) fn settle_status(e: &Env, p: &mut Project) {
+    if p.status == ProjectStatus::Funding && e.ledger().timestamp() >= p.deadline {
+        p.status = if p.raised >= p.goal { ProjectStatus::Funded } else { ProjectStatus::Failed };
+        write_project(e, p);
+        e.events().publish((symbol_short!("proj_end"), p.id), p.status);
+    }
+}
+
+fn read_contribution(e: &Env, id: u32, owner: &Address) -> i128 {
+    e.storage().persistent().get(&DataKey::Contribution(id, owner.clone())).unwrap_or(0)
+}
+
+fn write_contribution(e: &Env, id: u32, owner: &Address, amount: i128) {
+    let key = DataKey::Contribution(id, owner.clone());
+    if amount == 0 {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, &amount);
+    }
+}
+
+#[contractimpl]
+impl ProjectVault {
+    /// Registra un proyecto (solo admin). `token` debe ser un asset aceptado.
+    /// Devuelve el id.
+    pub fn register_project(
+        e: Env,
+        admin: Address,
+        beneficiary: Address,
+        metadata_hash: BytesN<32>,
+        token: Address,
+        goal: i128,
+        deadline: u64,
+    ) -> u32 {
+        require_admin(&e, &admin);
+        if goal <= 0 {
+            panic!("goal must be > 0");
+        }
+        if deadline <= e.ledger().timestamp() {
+            panic!("deadline in the past");
+        }
+        if !read_assets(&e).contains(&token) {
+            panic!("asset not allowed");
+        }
+
+        let id = e.storage().instance().get::<_, u32>(&DataKey::ProjectSeq).unwrap_or(0) + 1;
+        e.storage().instance().set(&DataKey::ProjectSeq, &id);
+
+        write_project(&e, &Project {
+            id,
+            beneficiary,
+            metadata_hash,
+            token,
+            goal,
+            deadline,
+            raised: 0,
+            status: ProjectStatus::Funding,
+            released: 0,
+            withdrawn: 0,
+        });
+        e.events().publish((symbol_short!("project"), id), goal);
+        id
+    }
+
+    /// Aporta `amount` del token del proyecto `id` desde `from`.
+    pub fn deposit_to_project(e: Env, from: Address, id: u32, amount: i128) {
+        from.require_auth();
+        if amount <= 0 {
+            panic!("amount must be > 0");
+        }
+        let mut p = read_project(&e, id);
+        settle_status(&e, &mut p);
+        if p.status != ProjectStatus::Funding {
+            panic!("project not funding");
+        }
+        // Los aportantes tienen que conocer los hitos antes de aportar
+        if !e.storage().persistent().has(&DataKey::Milestones(id)) {
+            panic!("milestones not declared");
+        }
+
+        p.raised += amount;
+        write_project(&e, &p);
+        write_contribution(&e, id, &from, read_contribution(&e, id, &from) + amount);
+
+        TokenClient::new(&e, &p.token).transfer(&from, &e.current_contract_address(), &amount);
+        add_total(&e, &p.token, amount);
+        surplus(&e, &p.token);
+    }
+
+    /// Devuelve a `owner` su aporte a un proyecto que no llegó a la meta.
+    pub fn refund(e: Env, owner: Address, id: u32) -> i128 {
+        owner.require_auth();
+        let mut p = read_project(&e, id);
+        settle_status(&e, &mut p);
+        if p.status != ProjectStatus::Failed {
+            panic!("project not failed");
+        }
+
+        let amount = read_contribution(&e, id, &owner);
+        if amount <= 0 {
+            panic!("nothing to refund");
+        }
+        write_contribution(&e, id, &owner, 0);
+        add_total(&e, &p.token, -amount);
+
+        TokenClient::new(&e, &p.token).transfer(&e.current_contract_address(), &owner, &amount);
+        surplus(&e, &p.token);
+        amount
+    }
+
+    /// Cierra el proyecto si ya venció su plazo. Lo puede llamar cualquiera.
+    pub fn finalize_project(e: Env, id: u32) -> ProjectStatus {
+        let mut p = read_project(&e, id);
+        settle_status(&e, &mut p);
+        p.status
+    }
+
+    pub fn project(e: Env, id: u32) -> Project {
+        read_project(&e, id)
+    }
+
+    pub fn project_count(e: Env) -> u32 {
+        e.storage().instance().get(&DataKey::ProjectSeq).unwrap_or(0)
+    }
+
+    /// Total aportado al proyecto `id`.
+    pub fn project_total(e: Env, id: u32) -> i128 {
+        read_project(&e, id).raised
+    }
+
+    pub fn contribution(e: Env, id: u32, owner: Address) -> i128 {
+        read_contribution(&e, id, &owner)
+    }
+}
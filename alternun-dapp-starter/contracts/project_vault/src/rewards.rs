@@ -0,0 +1,247 @@
+//! Rewards para stakers del vault con acumulador reward-per-share.
+//!
+//! Por cada token bloqueado (`stake`) el admin habilita hasta `MAX_REWARD_TOKENS`
+//! tokens de reward (ePT, stable, ...). Un funder deposita `amount` de reward para
+//! un epoch de `duration` segundos y se libera linealmente hasta su fin. Lo
+//! liberado se reparte pro-rata al peso de cada posición (`amount` por el
+//! multiplicador de su tier). Antes de cualquier cambio de peso se liquida lo
+//! devengado por el dueño con el peso anterior, así depósitos y retiros a mitad
+//! de epoch sólo cuentan desde/hasta ese momento.
+//! Lo que un epoch no llegó a liberar por falta de peso lo recupera el admin con
+//! `refund_rewards` o se suma al siguiente `fund_rewards`.
+
+use alternun_math::{mul_div, Rounding, BPS};
+use soroban_sdk::{
+    contractimpl, contracttype, symbol_short,
+    token::Client as TokenClient,
+    Address, Env, Vec,
+};
+
+use crate::{require_admin, DataKey, Position, ProjectVault, ProjectVaultClient};
+
+// Escala del acumulador
+const ACC_PRECISION: i128 = 1_000_000_000_000_000_000;
+const MAX_REWARD_TOKENS: u32 = 5;
+
+/// Estado de un reward para un token bloqueado.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardStream {
+    pub acc_per_share: i128, // reward por unidad de peso, escalado por 1e18
+    pub last_update: u64,
+    pub finish: u64,         // fin del epoch vigente
+    pub undistributed: i128, // fondeado y aún no liberado
+}
+
+/// Reward de un dueño en un par (stake, reward).
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct UserReward {
+    pub paid_per_share: i128, // acumulador al último checkpoint
+    pub accrued: i128,        // devengado y no reclamado
+}
+
+pub(crate) fn weight_of(pos: &Position) -> i128 {
+    mul_div(pos.amount, pos.multiplier_bps as i128, BPS, Rounding::Floor).expect("math overflow")
+}
+
+fn read_reward_tokens(e: &Env, stake: &Address) -> Vec<Address> {
+    e.storage().persistent().get(&DataKey::RewardTokens(stake.clone())).unwrap_or(Vec::new(e))
+}
+
+fn read_total_weight(e: &Env, stake: &Address) -> i128 {
+    e.storage().persistent().get(&DataKey::TotalWeight(stake.clone())).unwrap_or(0)
+}
+
+fn read_stream(e: &Env, stake: &Address, reward: &Address) -> RewardStream {
+    e.storage().persistent().get(&DataKey::RewardStream(stake.clone(), reward.clone())).unwrap_or(RewardStream {
+        acc_per_share: 0,
+        last_update: e.ledger().timestamp(),
+        finish: 0,
+        undistributed: 0,
+    })
+}
+
+fn read_user(e: &Env, owner: &Address, stake: &Address, reward: &Address) -> UserReward {
+    e.storage()
+        .persistent()
+        .get(&DataKey::UserReward(owner.clone(), stake.clone(), reward.clone()))
+        .unwrap_or_default()
+}
+
+fn write_user(e: &Env, owner: &Address, stake: &Address, reward: &Address, u: &UserReward) {
+    e.storage().persistent().set(&DataKey::UserReward(owner.clone(), stake.clone(), reward.clone()), u);
+}
+
+// Libera lo que corresponde hasta `now` (nunca más allá de `finish`) y lo suma
+// al acumulador. Sin peso bloqueado no libera nada: lo pendiente se reparte en lo
+// que queda del epoch, y si el epoch termina sin peso queda para `refund_rewards`
+// o para el próximo `fund_rewards`.
+fn accrue(s: &mut RewardStream, now: u64, total_weight: i128) {
+    let end = now.min(s.finish);
+    if end > s.last_update && s.undistributed > 0 && total_weight > 0 {
+        let released = if end == s.finish {
+            s.undistributed
+        } else {
+            let elapsed = (end - s.last_update) as i128;
+            let left = (s.finish - s.last_update) as i128;
+            mul_div(s.undistributed, elapsed, left, Rounding::Floor).expect("math overflow")
+        };
+        s.undistributed -= released;
+        s.acc_per_share += mul_div(released, ACC_PRECISION, total_weight, Rounding::Floor).expect("math overflow");
+    }
+    s.last_update = now;
+}
+
+fn update_stream(e: &Env, stake: &Address, reward: &Address, total_weight: i128) -> RewardStream {
+    let mut s = read_stream(e, stake, reward);
+    accrue(&mut s, e.ledger().timestamp(), total_weight);
+    e.storage().persistent().set(&DataKey::RewardStream(stake.clone(), reward.clone()), &s);
+    s
+}
+
+fn earned(u: &UserReward, acc_per_share: i128, weight: i128) -> i128 {
+    u.accrued + mul_div(weight, acc_per_share - u.paid_per_share, ACC_PRECISION, Rounding::Floor).expect("math overflow")
+}
+
+// Devenga para `owner` con `weight` hasta ahora en `reward`.
+fn settle_one(e: &Env, owner: &Address, stake: &Address, reward: &Address, weight: i128) -> UserReward {
+    let s = update_stream(e, stake, reward, read_total_weight(e, stake));
+    let mut u = read_user(e, owner, stake, reward);
+    u.accrued = earned(&u, s.acc_per_share, weight);
+    u.paid_per_share = s.acc_per_share;
+    write_user(e, owner, stake, reward, &u);
+    u
+}
+
+/// Se llama antes de cambiar la posición de `owner` en `stake`: liquida lo
+/// devengado con el peso `old_weight` y ajusta el peso total a `new_weight`.
+pub(crate) fn on_weight_change(e: &Env, owner: &Address, stake: &Address, old_weight: i128, new_weight: i128) {
+    for reward in read_reward_tokens(e, stake).iter() {
+        settle_one(e, owner, stake, &reward, old_weight);
+    }
+    if old_weight != new_weight {
+        let total = read_total_weight(e, stake) - old_weight + new_weight;
+        e.storage().persistent().set(&DataKey::TotalWeight(stake.clone()), &total);
+    }
+}
+
+fn current_weight(e: &Env, owner: &Address, stake: &Address) -> i128 {
+    crate::read_position(e, owner, stake).map(|p| weight_of(&p)).unwrap_or(0)
+}
+
+#[contractimpl]
+impl ProjectVault {
+    /// Habilita `reward` como token de reward para quienes bloquean `stake` (solo admin).
+    pub fn add_reward_token(e: Env, admin: Address, stake: Address, reward: Address) {
+        require_admin(&e, &admin);
+        let mut tokens = read_reward_tokens(&e, &stake);
+        if tokens.contains(&reward) {
+            return;
+        }
+        if tokens.len() >= MAX_REWARD_TOKENS {
+            panic!("too many reward tokens");
+        }
+        tokens.push_back(reward);
+        e.storage().persistent().set(&DataKey::RewardTokens(stake), &tokens);
+    }
+
+    pub fn reward_tokens(e: Env, stake: Address) -> Vec<Address> {
+        read_reward_tokens(&e, &stake)
+    }
+
+    /// Deposita `amount` de `reward` para repartir entre quienes bloquean `stake`
+    /// durante los próximos `duration` segundos. Lo no liberado de un epoch anterior
+    /// se suma al nuevo. Cualquiera puede fondear, pero con un epoch en curso no se
+    /// puede adelantar su fin ni bajar el ritmo de liberación: así un fondeo chico
+    /// no vuelca todo lo pendiente de una vez ni lo estira.
+    pub fn fund_rewards(e: Env, funder: Address, stake: Address, reward: Address, amount: i128, duration: u64) {
+        funder.require_auth();
+        if amount <= 0 || duration == 0 {
+            panic!("invalid funding");
+        }
+        if !read_reward_tokens(&e, &stake).contains(&reward) {
+            panic!("reward token not enabled");
+        }
+        let now = e.ledger().timestamp();
+        let finish = match now.checked_add(duration) {
+            Some(f) => f,
+            None => panic!("invalid funding"),
+        };
+
+        let mut s = update_stream(&e, &stake, &reward, read_total_weight(&e, &stake));
+        if s.finish > now && s.undistributed > 0 {
+            if finish < s.finish {
+                panic!("cannot shorten epoch");
+            }
+            // (pendiente + amount) / (finish - now) >= pendiente / (s.finish - now)
+            let new_side = (s.undistributed + amount).checked_mul((s.finish - now) as i128).expect("math overflow");
+            let old_side = s.undistributed.checked_mul((finish - now) as i128).expect("math overflow");
+            if new_side < old_side {
+                panic!("cannot lower reward rate");
+            }
+        }
+
+        TokenClient::new(&e, &reward).transfer(&funder, &e.current_contract_address(), &amount);
+
+        s.undistributed += amount;
+        s.finish = finish;
+        e.storage().persistent().set(&DataKey::RewardStream(stake.clone(), reward.clone()), &s);
+
+        e.events().publish((symbol_short!("funded"), stake, reward), (amount, s.finish));
+    }
+
+    /// Devuelve a `to` lo que quedó sin liberar de `reward` al terminar el epoch
+    /// (tramos sin nadie bloqueado en `stake`). Solo admin y sólo después de
+    /// `finish`. Devuelve el monto.
+    pub fn refund_rewards(e: Env, admin: Address, stake: Address, reward: Address, to: Address) -> i128 {
+        require_admin(&e, &admin);
+        let mut s = update_stream(&e, &stake, &reward, read_total_weight(&e, &stake));
+        if e.ledger().timestamp() < s.finish {
+            panic!("epoch not finished");
+        }
+        let amount = s.undistributed;
+        if amount <= 0 {
+            panic!("nothing to refund");
+        }
+        s.undistributed = 0;
+        e.storage().persistent().set(&DataKey::RewardStream(stake.clone(), reward.clone()), &s);
+
+        TokenClient::new(&e, &reward).transfer(&e.current_contract_address(), &to, &amount);
+        e.events().publish((symbol_short!("rw_refund"), stake, reward), amount);
+        amount
+    }
+
+    /// Paga a `owner` todo lo devengado en `reward` por su posición en `stake`.
+    pub fn claim_rewards(e: Env, owner: Address, stake: Address, reward: Address) -> i128 {
+        owner.require_auth();
+        let weight = current_weight(&e, &owner, &stake);
+        let mut u = settle_one(&e, &owner, &stake, &reward, weight);
+        let amount = u.accrued;
+        if amount <= 0 {
+            panic!("nothing to claim");
+        }
+        u.accrued = 0;
+        write_user(&e, &owner, &stake, &reward, &u);
+
+        TokenClient::new(&e, &reward).transfer(&e.current_contract_address(), &owner, &amount);
+        amount
+    }
+
+    /// Devengado y no reclamado a la fecha (no modifica estado).
+    pub fn pending_rewards(e: Env, owner: Address, stake: Address, reward: Address) -> i128 {
+        let mut s = read_stream(&e, &stake, &reward);
+        accrue(&mut s, e.ledger().timestamp(), read_total_weight(&e, &stake));
+        let u = read_user(&e, &owner, &stake, &reward);
+        earned(&u, s.acc_per_share, current_weight(&e, &owner, &stake))
+    }
+
+    pub fn reward_stream(e: Env, stake: Address, reward: Address) -> RewardStream {
+        read_stream(&e, &stake, &reward)
+    }
+
+    /// Suma de pesos (monto por multiplicador) bloqueados en `stake`.
+    pub fn total_weight(e: Env, stake: Address) -> i128 {
+        read_total_weight(&e, &stake)
+    }
+}
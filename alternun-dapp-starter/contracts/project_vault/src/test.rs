@@ -0,0 +1,616 @@
+#![cfg(test)]
+
+extern crate std;
+
+use super::*;
+
+use soroban_sdk::testutils::{Address as _, Ledger, MockAuth, MockAuthInvoke};
+use soroban_sdk::{vec, BytesN, IntoVal};
+use soroban_sdk::token::StellarAssetClient as TokenAdminClient;
+
+const DAY: u64 = 24 * 60 * 60;
+
+struct Setup<'a> {
+    env: Env,
+    admin: Address,
+    gbt: TokenClient<'a>,
+    pgbt: TokenClient<'a>,
+    vault: ProjectVaultClient<'a>,
+}
+
+// Vault con GBT aceptado y pGBT (un SAC con el vault de admin) como recibo
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+    env.ledger().set_timestamp(1_000_000);
+
+    let admin = Address::generate(&env);
+    let vault = ProjectVaultClient::new(&env, &env.register_contract(None, ProjectVault));
+    vault.init(&admin);
+
+    let gbt = env.register_stellar_asset_contract_v2(admin.clone()).address();
+    let pgbt = env.register_stellar_asset_contract_v2(vault.address.clone()).address();
+    vault.set_asset(&admin, &gbt, &true);
+    vault.set_receipt_token(&admin, &gbt, &pgbt);
+
+    Setup {
+        gbt: TokenClient::new(&env, &gbt),
+        pgbt: TokenClient::new(&env, &pgbt),
+        env,
+        admin,
+        vault,
+    }
+}
+
+impl Setup<'_> {
+    fn user(&self, gbt: i128) -> Address {
+        let user = Address::generate(&self.env);
+        TokenAdminClient::new(&self.env, &self.gbt.address).mint(&user, &gbt);
+        user
+    }
+
+    // Token de reward habilitado para GBT, con `amount` en manos del funder
+    fn reward(&self, amount: i128) -> (TokenClient<'_>, Address) {
+        let reward = self.env.register_stellar_asset_contract_v2(self.admin.clone()).address();
+        self.vault.add_reward_token(&self.admin, &self.gbt.address, &reward);
+        let funder = Address::generate(&self.env);
+        TokenAdminClient::new(&self.env, &reward).mint(&funder, &amount);
+        (TokenClient::new(&self.env, &reward), funder)
+    }
+
+    fn claim(&self, owner: &Address, reward: &TokenClient) -> i128 {
+        match self.vault.try_claim_rewards(owner, &self.gbt.address, &reward.address) {
+            Ok(Ok(amount)) => amount,
+            _ => 0,
+        }
+    }
+
+    // Proyecto en GBT con plazo de 30 días e hitos de 60% / 40%
+    fn project(&self, beneficiary: &Address, goal: i128) -> u32 {
+        let deadline = self.env.ledger().timestamp() + 30 * DAY;
+        let hash = BytesN::from_array(&self.env, &[7; 32]);
+        let id = self.vault.register_project(&self.admin, beneficiary, &hash, &self.gbt.address, &goal, &deadline);
+        self.vault.declare_milestones(beneficiary, &id, &vec![&self.env, 6_000, 4_000]);
+        id
+    }
+
+    fn set_ledger(&self, sequence: u32) {
+        self.env.ledger().set_sequence_number(sequence);
+    }
+
+    fn advance(&self, secs: u64) {
+        let now = self.env.ledger().timestamp();
+        self.env.ledger().set_timestamp(now + secs);
+    }
+}
+
+#[test]
+fn deposit_mints_receipt_and_withdraw_burns_it() {
+    let s = setup();
+    let alice = s.user(1_000);
+
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &0);
+    assert_eq!(s.pgbt.balance(&alice), 1_000);
+    assert_eq!(s.vault.total_locked(&s.gbt.address), 1_000);
+
+    s.advance(90 * DAY);
+    s.vault.withdraw(&alice, &s.gbt.address, &400);
+    assert_eq!(s.pgbt.balance(&alice), 600);
+    assert_eq!(s.gbt.balance(&alice), 400);
+    assert_eq!(s.vault.position(&alice, &s.gbt.address).unwrap().amount, 600);
+}
+
+#[test]
+fn receipt_holder_redeems_matured_position() {
+    let s = setup();
+    let alice = s.user(1_000);
+    let bob = Address::generate(&s.env);
+
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &0);
+    s.pgbt.transfer(&alice, &bob, &700);
+
+    s.advance(90 * DAY);
+    s.vault.redeem(&bob, &alice, &s.gbt.address, &700);
+    assert_eq!(s.gbt.balance(&bob), 700);
+    assert_eq!(s.pgbt.balance(&bob), 0);
+    assert_eq!(s.vault.position(&alice, &s.gbt.address).unwrap().amount, 300);
+    assert_eq!(s.vault.total_locked(&s.gbt.address), 300);
+    assert_eq!(s.vault.reconcile(&s.gbt.address), 0);
+}
+
+#[test]
+fn locked_depositor_cannot_redeem_against_another_position() {
+    let s = setup();
+    let alice = s.user(1_000);
+    let carol = s.user(1_000);
+
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &0);
+    s.advance(90 * DAY);
+    // Carol bloquea 12 meses y quiere salir con la posición vencida de Alice
+    s.vault.deposit(&s.gbt.address, &carol, &1_000, &2);
+
+    // Carol firma todo lo suyo (el canje y la quema de sus recibos), Alice nada
+    let burn = MockAuthInvoke {
+        contract: &s.pgbt.address,
+        fn_name: "burn",
+        args: (carol.clone(), 1_000i128).into_val(&s.env),
+        sub_invokes: &[],
+    };
+    let only_carol = MockAuth {
+        address: &carol,
+        invoke: &MockAuthInvoke {
+            contract: &s.vault.address,
+            fn_name: "redeem",
+            args: (carol.clone(), alice.clone(), s.gbt.address.clone(), 1_000i128).into_val(&s.env),
+            sub_invokes: &[burn],
+        },
+    };
+    assert!(s.vault.mock_auths(&[only_carol]).try_redeem(&carol, &alice, &s.gbt.address, &1_000).is_err());
+
+    assert_eq!(s.vault.position(&alice, &s.gbt.address).unwrap().amount, 1_000);
+    assert_eq!(s.vault.position(&carol, &s.gbt.address).unwrap().amount, 1_000);
+    assert_eq!(s.pgbt.balance(&carol), 1_000);
+    assert_eq!(s.gbt.balance(&carol), 0);
+}
+
+#[test]
+#[should_panic(expected = "position locked")]
+fn redeem_waits_for_maturity() {
+    let s = setup();
+    let alice = s.user(1_000);
+    let bob = Address::generate(&s.env);
+
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &0);
+    s.pgbt.transfer(&alice, &bob, &1_000);
+    s.vault.redeem(&bob, &alice, &s.gbt.address, &1_000);
+}
+
+#[test]
+fn receipt_must_be_administered_by_vault() {
+    let s = setup();
+    let usdc = s.env.register_stellar_asset_contract_v2(s.admin.clone()).address();
+    s.vault.set_asset(&s.admin, &usdc, &true);
+
+    // Un recibo inicializado por otro admin no se acepta
+    let foreign = s.env.register_stellar_asset_contract_v2(s.admin.clone()).address();
+    assert!(s.vault.try_set_receipt_token(&s.admin, &usdc, &foreign).is_err());
+    assert_eq!(s.vault.receipt_token(&usdc), None);
+
+    // Sin nada bloqueado se puede reemplazar; con depósitos ya no
+    let receipt = s.env.register_stellar_asset_contract_v2(s.vault.address.clone()).address();
+    s.vault.set_receipt_token(&s.admin, &s.gbt.address, &receipt);
+    assert_eq!(s.vault.receipt_token(&s.gbt.address), Some(receipt.clone()));
+    let alice = s.user(1_000);
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &0);
+    assert!(s.vault.try_set_receipt_token(&s.admin, &s.gbt.address, &s.pgbt.address).is_err());
+}
+
+#[test]
+fn owner_without_receipts_cannot_withdraw() {
+    let s = setup();
+    let alice = s.user(1_000);
+    let bob = Address::generate(&s.env);
+
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &0);
+    s.pgbt.transfer(&alice, &bob, &1_000);
+
+    s.advance(90 * DAY);
+    assert!(s.vault.try_withdraw(&alice, &s.gbt.address, &1).is_err());
+    assert_eq!(s.vault.position(&alice, &s.gbt.address).unwrap().amount, 1_000);
+}
+
+#[test]
+#[should_panic(expected = "no receipt token")]
+fn redeem_needs_receipt_token() {
+    let s = setup();
+    let usdc = s.env.register_stellar_asset_contract_v2(s.admin.clone()).address();
+    s.vault.set_asset(&s.admin, &usdc, &true);
+    let alice = Address::generate(&s.env);
+    TokenAdminClient::new(&s.env, &usdc).mint(&alice, &1_000);
+
+    s.vault.deposit(&usdc, &alice, &1_000, &0);
+    s.advance(90 * DAY);
+    s.vault.redeem(&Address::generate(&s.env), &alice, &usdc, &1_000);
+}
+
+#[test]
+fn matured_position_drops_to_1x_on_poke() {
+    let s = setup();
+    s.vault.set_voting_asset(&s.admin, &s.gbt.address);
+    let alice = s.user(1_000);
+    let bob = s.user(1_000);
+
+    // 12 meses a 1.5x contra 3 meses a 1x
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &2);
+    s.vault.deposit(&s.gbt.address, &bob, &1_000, &0);
+    assert_eq!(s.vault.total_weight(&s.gbt.address), 2_500);
+    assert_eq!(s.vault.voting_power(&alice), 1_500);
+
+    s.advance(365 * DAY);
+    assert!(s.vault.maturity(&alice, &s.gbt.address).unwrap().matured);
+
+    // Cualquiera puede aplicarlo; repetirlo no cambia nada
+    s.vault.poke(&alice, &s.gbt.address);
+    s.vault.poke(&alice, &s.gbt.address);
+    assert_eq!(s.vault.position(&alice, &s.gbt.address).unwrap().multiplier_bps, 10_000);
+    assert_eq!(s.vault.total_weight(&s.gbt.address), 2_000);
+    assert_eq!(s.vault.voting_power(&alice), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "position locked")]
+fn poke_waits_for_maturity() {
+    let s = setup();
+    let alice = s.user(1_000);
+
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &2);
+    s.advance(364 * DAY);
+    s.vault.poke(&alice, &s.gbt.address);
+}
+
+#[test]
+fn partial_withdraw_after_maturity_reweights_at_1x() {
+    let s = setup();
+    let alice = s.user(1_000);
+
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &1);
+    assert_eq!(s.vault.total_weight(&s.gbt.address), 1_250);
+
+    s.advance(180 * DAY);
+    s.vault.withdraw(&alice, &s.gbt.address, &400);
+    assert_eq!(s.vault.total_weight(&s.gbt.address), 600);
+}
+
+#[test]
+fn rewards_split_by_tier_weight() {
+    let s = setup();
+    let (reward, funder) = s.reward(2_500_000);
+    let alice = s.user(1_000);
+    let bob = s.user(1_000);
+
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &2); // 1.5x
+    s.vault.deposit(&s.gbt.address, &bob, &1_000, &0); // 1x
+    s.vault.fund_rewards(&funder, &s.gbt.address, &reward.address, &2_500_000, &(10 * DAY));
+
+    s.advance(10 * DAY);
+    assert_eq!(s.claim(&alice, &reward), 1_500_000);
+    assert_eq!(s.claim(&bob, &reward), 1_000_000);
+}
+
+#[test]
+fn rewards_count_only_while_staked() {
+    let s = setup();
+    let (reward, funder) = s.reward(2_000_000);
+    let alice = s.user(1_000);
+    let bob = s.user(1_000);
+
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &0);
+    s.vault.fund_rewards(&funder, &s.gbt.address, &reward.address, &2_000_000, &(200 * DAY));
+
+    // Bob entra en el día 50 y sale en el 150 (su bloqueo de 90 días ya venció)
+    s.advance(50 * DAY);
+    s.vault.deposit(&s.gbt.address, &bob, &1_000, &0);
+    s.advance(100 * DAY);
+    s.vault.withdraw(&bob, &s.gbt.address, &1_000);
+    s.advance(50 * DAY);
+
+    // Alice: 500k + 1M/2 + 500k; Bob: 1M/2
+    let a = s.claim(&alice, &reward);
+    let b = s.claim(&bob, &reward);
+    assert!((1_499_998..=1_500_000).contains(&a), "alice {a}");
+    assert!((499_998..=500_000).contains(&b), "bob {b}");
+    assert!(a + b <= 2_000_000);
+}
+
+#[test]
+fn funding_without_stakers_waits_for_weight() {
+    let s = setup();
+    let (reward, funder) = s.reward(1_000_000);
+    let alice = s.user(1_000);
+
+    s.vault.fund_rewards(&funder, &s.gbt.address, &reward.address, &1_000_000, &(100 * DAY));
+    assert_eq!(s.vault.total_weight(&s.gbt.address), 0);
+
+    // Nada se libera sin peso: lo pendiente se reparte en el resto del epoch
+    s.advance(50 * DAY);
+    assert_eq!(s.vault.reward_stream(&s.gbt.address, &reward.address).undistributed, 1_000_000);
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &0);
+    s.advance(50 * DAY);
+    assert_eq!(s.claim(&alice, &reward), 1_000_000);
+}
+
+#[test]
+fn unreleased_rewards_are_refunded_after_finish() {
+    let s = setup();
+    let (reward, funder) = s.reward(1_000_000);
+    let alice = s.user(1_000);
+    let treasury = Address::generate(&s.env);
+
+    s.vault.fund_rewards(&funder, &s.gbt.address, &reward.address, &1_000_000, &(10 * DAY));
+    assert!(s.vault.try_refund_rewards(&s.admin, &s.gbt.address, &reward.address, &treasury).is_err());
+
+    // El epoch termina sin nadie bloqueado: quien entra después no se lo lleva
+    s.advance(11 * DAY);
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &0);
+    s.advance(DAY);
+    assert_eq!(s.vault.pending_rewards(&alice, &s.gbt.address, &reward.address), 0);
+
+    assert_eq!(s.vault.refund_rewards(&s.admin, &s.gbt.address, &reward.address, &treasury), 1_000_000);
+    assert_eq!(reward.balance(&treasury), 1_000_000);
+    assert_eq!(reward.balance(&s.vault.address), 0);
+}
+
+#[test]
+fn claims_never_exceed_funding() {
+    let s = setup();
+    let funded = 7_777_777;
+    let (reward, funder) = s.reward(funded);
+    let stakers: std::vec::Vec<Address> = (0..4).map(|_| s.user(1_000_000)).collect();
+
+    let mut claimed = 0;
+    s.vault.deposit(&s.gbt.address, &stakers[0], &333_333, &2);
+    s.vault.fund_rewards(&funder, &s.gbt.address, &reward.address, &(funded / 2), &(97 * DAY));
+    for (i, who) in stakers.iter().enumerate() {
+        s.advance(7 * DAY + i as u64 * 3_001);
+        s.vault.deposit(&s.gbt.address, who, &(10_007 * (i as i128 + 1)), &((2 + i) as u32 % 3));
+        claimed += s.claim(&stakers[(i + 1) % 4], &reward);
+    }
+    // Segundo fondeo a mitad de epoch: lo pendiente se suma
+    s.vault.fund_rewards(&funder, &s.gbt.address, &reward.address, &(funded - funded / 2), &(80 * DAY));
+    s.advance(100 * DAY);
+    s.vault.withdraw(&stakers[1], &s.gbt.address, &10_007);
+    s.advance(100 * DAY);
+
+    for who in stakers.iter() {
+        claimed += s.claim(who, &reward);
+    }
+    // Todo lo fondeado se liberó; sólo queda el polvo del redondeo hacia abajo
+    assert!(claimed <= funded);
+    assert!(funded - claimed < 10, "dust {}", funded - claimed);
+    assert_eq!(reward.balance(&s.vault.address), funded - claimed);
+}
+
+#[test]
+fn failed_project_refunds_each_contributor() {
+    let s = setup();
+    let beneficiary = Address::generate(&s.env);
+    let id = s.project(&beneficiary, 10_000);
+    let alice = s.user(3_000);
+    let bob = s.user(2_000);
+
+    s.vault.deposit_to_project(&alice, &id, &3_000);
+    s.vault.deposit_to_project(&bob, &id, &2_000);
+    assert_eq!(s.vault.project_total(&id), 5_000);
+    assert_eq!(s.vault.total_locked(&s.gbt.address), 5_000);
+
+    // Antes del plazo no hay reembolso
+    assert!(s.vault.try_refund(&alice, &id).is_err());
+
+    s.advance(30 * DAY);
+    assert!(s.vault.try_deposit_to_project(&alice, &id, &1).is_err());
+    assert_eq!(s.vault.finalize_project(&id), ProjectStatus::Failed);
+
+    assert_eq!(s.vault.refund(&alice, &id), 3_000);
+    assert_eq!(s.vault.refund(&bob, &id), 2_000);
+    assert!(s.vault.try_refund(&alice, &id).is_err());
+    assert_eq!(s.gbt.balance(&alice), 3_000);
+    assert_eq!(s.gbt.balance(&bob), 2_000);
+    assert_eq!(s.vault.contribution(&id, &alice), 0);
+    assert_eq!(s.vault.total_locked(&s.gbt.address), 0);
+    assert_eq!(s.vault.reconcile(&s.gbt.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "project not failed")]
+fn funded_project_has_no_refunds() {
+    let s = setup();
+    let beneficiary = Address::generate(&s.env);
+    let id = s.project(&beneficiary, 1_000);
+    let alice = s.user(1_000);
+
+    s.vault.deposit_to_project(&alice, &id, &1_000);
+    s.advance(30 * DAY);
+    s.vault.refund(&alice, &id);
+}
+
+#[test]
+#[should_panic(expected = "milestones not declared")]
+fn contributions_need_declared_milestones() {
+    let s = setup();
+    let beneficiary = Address::generate(&s.env);
+    let hash = BytesN::from_array(&s.env, &[7; 32]);
+    let deadline = s.env.ledger().timestamp() + DAY;
+    let id = s.vault.register_project(&s.admin, &beneficiary, &hash, &s.gbt.address, &1_000, &deadline);
+    let alice = s.user(1_000);
+
+    s.vault.deposit_to_project(&alice, &id, &1_000);
+}
+
+// Proyecto fondeado con 3 verificadores y umbral 2
+fn funded_project(s: &Setup) -> (u32, Address, std::vec::Vec<Address>) {
+    let beneficiary = Address::generate(&s.env);
+    let verifiers: std::vec::Vec<Address> = (0..3).map(|_| Address::generate(&s.env)).collect();
+    s.vault.set_verifiers(
+        &s.admin,
+        &vec![&s.env, verifiers[0].clone(), verifiers[1].clone(), verifiers[2].clone()],
+        &2,
+    );
+    let id = s.project(&beneficiary, 1_000);
+    let alice = s.user(1_001);
+    s.vault.deposit_to_project(&alice, &id, &1_001);
+    s.advance(30 * DAY);
+    (id, beneficiary, verifiers)
+}
+
+#[test]
+fn milestones_release_funds_at_threshold() {
+    let s = setup();
+    let (id, beneficiary, verifiers) = funded_project(&s);
+    let evidence = BytesN::from_array(&s.env, &[1; 32]);
+
+    // Sin aprobaciones no hay nada que retirar
+    assert!(s.vault.try_withdraw_released(&beneficiary, &id).is_err());
+
+    s.vault.submit_evidence(&beneficiary, &id, &0, &evidence);
+    s.vault.approve_milestone(&verifiers[0], &id, &0, &evidence);
+    assert_eq!(s.vault.project(&id).released, 0);
+    s.vault.approve_milestone(&verifiers[1], &id, &0, &evidence);
+    assert!(s.vault.milestones(&id).get(0).unwrap().approved);
+
+    // 60% de 1_001, redondeado hacia abajo
+    assert_eq!(s.vault.withdraw_released(&beneficiary, &id), 600);
+    assert_eq!(s.gbt.balance(&beneficiary), 600);
+
+    // El último hito libera el resto exacto
+    let evidence2 = BytesN::from_array(&s.env, &[2; 32]);
+    s.vault.submit_evidence(&beneficiary, &id, &1, &evidence2);
+    s.vault.approve_milestone(&verifiers[2], &id, &1, &evidence2);
+    s.vault.approve_milestone(&verifiers[0], &id, &1, &evidence2);
+    assert_eq!(s.vault.withdraw_released(&beneficiary, &id), 401);
+    assert_eq!(s.vault.total_locked(&s.gbt.address), 0);
+    assert_eq!(s.vault.reconcile(&s.gbt.address), 0);
+}
+
+#[test]
+fn new_evidence_resets_approvals() {
+    let s = setup();
+    let (id, beneficiary, verifiers) = funded_project(&s);
+    let first = BytesN::from_array(&s.env, &[1; 32]);
+    let second = BytesN::from_array(&s.env, &[2; 32]);
+
+    s.vault.submit_evidence(&beneficiary, &id, &0, &first);
+    s.vault.approve_milestone(&verifiers[0], &id, &0, &first);
+    s.vault.submit_evidence(&beneficiary, &id, &0, &second);
+    assert!(s.vault.milestones(&id).get(0).unwrap().approvals.is_empty());
+
+    // Aprobar el hash anterior ya no sirve
+    assert!(s.vault.try_approve_milestone(&verifiers[1], &id, &0, &first).is_err());
+    s.vault.approve_milestone(&verifiers[1], &id, &0, &second);
+    assert!(!s.vault.milestones(&id).get(0).unwrap().approved);
+}
+
+#[test]
+#[should_panic(expected = "not verifier")]
+fn only_verifiers_approve_milestones() {
+    let s = setup();
+    let (id, beneficiary, _) = funded_project(&s);
+    let evidence = BytesN::from_array(&s.env, &[1; 32]);
+
+    s.vault.submit_evidence(&beneficiary, &id, &0, &evidence);
+    s.vault.approve_milestone(&beneficiary, &id, &0, &evidence);
+}
+
+#[test]
+#[should_panic(expected = "milestones must sum to 10000 bps")]
+fn milestones_must_cover_everything() {
+    let s = setup();
+    let beneficiary = Address::generate(&s.env);
+    let hash = BytesN::from_array(&s.env, &[7; 32]);
+    let deadline = s.env.ledger().timestamp() + DAY;
+    let id = s.vault.register_project(&s.admin, &beneficiary, &hash, &s.gbt.address, &1_000, &deadline);
+    s.vault.declare_milestones(&beneficiary, &id, &vec![&s.env, 6_000, 3_000]);
+}
+
+#[test]
+fn voting_power_is_checkpointed_per_ledger() {
+    let s = setup();
+    s.vault.set_voting_asset(&s.admin, &s.gbt.address);
+    let alice = s.user(10_000);
+
+    // Un depósito por ledger, y dos en el mismo ledger que pisan el checkpoint
+    for i in 1..=40u32 {
+        s.set_ledger(10 * i);
+        s.vault.deposit(&s.gbt.address, &alice, &100, &0);
+    }
+    s.vault.deposit(&s.gbt.address, &alice, &50, &0);
+    s.env.as_contract(&s.vault.address, || {
+        let count: u32 = s.env.storage().persistent().get(&DataKey::CheckpointCount(alice.clone())).unwrap();
+        assert_eq!(count, 40);
+    });
+
+    assert_eq!(s.vault.voting_power_at(&alice, &9), 0);
+    for i in 1..40u32 {
+        assert_eq!(s.vault.voting_power_at(&alice, &(10 * i)), 100 * i as i128);
+        assert_eq!(s.vault.voting_power_at(&alice, &(10 * i + 9)), 100 * i as i128);
+        assert_eq!(s.vault.total_voting_power_at(&(10 * i + 5)), 100 * i as i128);
+    }
+    assert_eq!(s.vault.voting_power_at(&alice, &400), 4_050);
+    assert_eq!(s.vault.voting_power(&alice), 4_050);
+}
+
+#[test]
+fn delegation_moves_power_without_changing_total() {
+    let s = setup();
+    s.vault.set_voting_asset(&s.admin, &s.gbt.address);
+    let alice = s.user(1_000);
+    let bob = s.user(1_000);
+    let carol = Address::generate(&s.env);
+
+    s.set_ledger(100);
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &2); // 1.5x
+    s.vault.deposit(&s.gbt.address, &bob, &1_000, &0);
+
+    s.set_ledger(200);
+    s.vault.delegate(&alice, &s.gbt.address, &carol);
+    assert_eq!(s.vault.delegate_of(&alice, &s.gbt.address), carol);
+
+    // Lo que deposite después también va al delegado
+    s.set_ledger(300);
+    TokenAdminClient::new(&s.env, &s.gbt.address).mint(&alice, &1_000);
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &2);
+
+    s.set_ledger(400);
+    s.vault.delegate(&alice, &s.gbt.address, &alice);
+
+    assert_eq!(s.vault.voting_power_at(&alice, &150), 1_500);
+    assert_eq!(s.vault.voting_power_at(&alice, &250), 0);
+    assert_eq!(s.vault.voting_power_at(&carol, &250), 1_500);
+    assert_eq!(s.vault.voting_power_at(&carol, &350), 3_000);
+    assert_eq!(s.vault.voting_power_at(&carol, &450), 0);
+    assert_eq!(s.vault.voting_power(&alice), 3_000);
+    assert_eq!(s.vault.voting_power(&bob), 1_000);
+    assert_eq!(s.vault.total_voting_power_at(&250), 2_500);
+    assert_eq!(s.vault.total_voting_power_at(&450), 4_000);
+}
+
+#[test]
+fn only_the_voting_asset_counts() {
+    let s = setup();
+    let usdc = s.env.register_stellar_asset_contract_v2(s.admin.clone()).address();
+    s.vault.set_asset(&s.admin, &usdc, &true);
+    s.vault.set_voting_asset(&s.admin, &s.gbt.address);
+    let alice = Address::generate(&s.env);
+    TokenAdminClient::new(&s.env, &usdc).mint(&alice, &1_000);
+
+    s.vault.deposit(&usdc, &alice, &1_000, &0);
+    assert_eq!(s.vault.voting_power(&alice), 0);
+    assert!(s.vault.try_set_voting_asset(&s.admin, &usdc).is_err());
+}
+
+#[test]
+fn top_ups_cannot_rush_or_stretch_an_epoch() {
+    let s = setup();
+    let (reward, funder) = s.reward(3_000_000);
+    let alice = s.user(1_000);
+    s.vault.deposit(&s.gbt.address, &alice, &1_000, &0);
+    s.vault.fund_rewards(&funder, &s.gbt.address, &reward.address, &1_000_000, &(100 * DAY));
+    s.advance(50 * DAY);
+
+    // 1 unidad con duración 1 volcaría los 500k pendientes de una vez
+    assert!(s.vault.try_fund_rewards(&funder, &s.gbt.address, &reward.address, &1, &1).is_err());
+    // 1 unidad a un año estiraría lo pendiente
+    assert!(s.vault.try_fund_rewards(&funder, &s.gbt.address, &reward.address, &1, &(365 * DAY)).is_err());
+    // Una duración que desborda `finish` tampoco pasa
+    assert!(s.vault.try_fund_rewards(&funder, &s.gbt.address, &reward.address, &1, &u64::MAX).is_err());
+    assert_eq!(s.vault.pending_rewards(&alice, &s.gbt.address, &reward.address), 500_000);
+
+    // Extender al doble con el mismo ritmo sí
+    s.vault.fund_rewards(&funder, &s.gbt.address, &reward.address, &500_000, &(100 * DAY));
+    let stream = s.vault.reward_stream(&s.gbt.address, &reward.address);
+    assert_eq!(stream.finish, s.env.ledger().timestamp() + 100 * DAY);
+    assert_eq!(stream.undistributed, 1_000_000);
+
+    s.advance(100 * DAY);
+    assert_eq!(s.claim(&alice, &reward), 1_500_000);
+}
@@ -0,0 +1,166 @@
+//! Tiers de bloqueo y salida anticipada.
+//!
+//! Cada tier fija un plazo de bloqueo y un multiplicador de rewards. Antes del
+//! vencimiento el dueño puede salir con `withdraw_early` pagando una penalidad en
+//! bps, que va al pool Recovery del treasury. El multiplicador rige sólo mientras
+//! dura el bloqueo: al vencer la posición vuelve a 1x, y cualquiera puede
+//! aplicarlo con `poke`.
+
+use alternun_math::{apply_bps, Rounding, BPS};
+use soroban_sdk::{
+    contractimpl, contracttype, symbol_short, vec,
+    token::Client as TokenClient,
+    Address, Env, Vec,
+};
+
+use crate::{
+    debit_position, read_position, require_admin, surplus, write_position, ProjectVault, ProjectVaultClient, State,
+};
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+const MAX_TIERS: u32 = 10;
+// Penalidad por defecto por salida anticipada (10%)
+const DEFAULT_PENALTY_BPS: u32 = 1_000;
+
+/// Plazo de bloqueo y multiplicador de rewards (10_000 = 1x).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tier {
+    pub lock_secs: u64,
+    pub multiplier_bps: u32,
+}
+
+/// Estado de vencimiento de una posición.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Maturity {
+    pub tier: u32,
+    pub multiplier_bps: u32,
+    pub lock_until: u64,
+    pub secs_left: u64,        // 0 si ya venció
+    pub matured: bool,
+    pub early_penalty: i128,   // penalidad si retirara todo ahora
+}
+
+// 3/6/12 meses con 1x / 1.25x / 1.5x
+fn default_tiers(e: &Env) -> Vec<Tier> {
+    vec![
+        e,
+        Tier { lock_secs: 90 * DAY_SECS, multiplier_bps: 10_000 },
+        Tier { lock_secs: 180 * DAY_SECS, multiplier_bps: 12_500 },
+        Tier { lock_secs: 365 * DAY_SECS, multiplier_bps: 15_000 },
+    ]
+}
+
+pub(crate) fn read_tiers(e: &Env) -> Vec<Tier> {
+    e.storage().instance().get(&State::TIERS).unwrap_or_else(|| default_tiers(e))
+}
+
+fn read_penalty_bps(e: &Env) -> u32 {
+    e.storage().instance().get(&State::PENALTY).unwrap_or(DEFAULT_PENALTY_BPS)
+}
+
+fn penalty_of(e: &Env, amount: i128) -> i128 {
+    apply_bps(amount, read_penalty_bps(e) as i128, Rounding::Ceil).expect("math overflow")
+}
+
+#[contractimpl]
+impl ProjectVault {
+    /// Reemplaza la tabla de tiers (solo admin). Los plazos deben ser estrictamente
+    /// crecientes y los multiplicadores >= 1x. Las posiciones existentes conservan
+    /// su bloqueo y multiplicador.
+    pub fn set_tiers(e: Env, admin: Address, tiers: Vec<Tier>) {
+        require_admin(&e, &admin);
+        if tiers.is_empty() || tiers.len() > MAX_TIERS {
+            panic!("invalid tiers");
+        }
+        let mut prev: Option<u64> = None;
+        for t in tiers.iter() {
+            if (t.multiplier_bps as i128) < BPS || prev.is_some_and(|p| t.lock_secs <= p) {
+                panic!("invalid tiers");
+            }
+            prev = Some(t.lock_secs);
+        }
+        e.storage().instance().set(&State::TIERS, &tiers);
+    }
+
+    pub fn tiers(e: Env) -> Vec<Tier> {
+        read_tiers(&e)
+    }
+
+    /// Configura la penalidad por salida anticipada y su destino, el pool Recovery
+    /// del treasury (solo admin).
+    pub fn set_early_exit(e: Env, admin: Address, penalty_bps: u32, recovery: Address) {
+        require_admin(&e, &admin);
+        if penalty_bps as i128 > BPS {
+            panic!("invalid penalty");
+        }
+        e.storage().instance().set(&State::PENALTY, &penalty_bps);
+        e.storage().instance().set(&State::RECOVERY, &recovery);
+    }
+
+    pub fn early_exit_penalty_bps(e: Env) -> u32 {
+        read_penalty_bps(&e)
+    }
+
+    pub fn maturity(e: Env, owner: Address, token: Address) -> Option<Maturity> {
+        let pos = read_position(&e, &owner, &token)?;
+        let now = e.ledger().timestamp();
+        let matured = now >= pos.lock_until;
+        Some(Maturity {
+            tier: pos.tier,
+            multiplier_bps: pos.multiplier_bps,
+            lock_until: pos.lock_until,
+            secs_left: pos.lock_until.saturating_sub(now),
+            matured,
+            early_penalty: if matured { 0 } else { penalty_of(&e, pos.amount) },
+        })
+    }
+
+    /// Baja a 1x el multiplicador de la posición vencida de `owner` en `token` y
+    /// recalcula su peso de rewards y votos. Sin auth: lo puede llamar cualquiera.
+    pub fn poke(e: Env, owner: Address, token: Address) {
+        let mut pos = match read_position(&e, &owner, &token) {
+            Some(p) => p,
+            None => panic!("no position"),
+        };
+        if e.ledger().timestamp() < pos.lock_until {
+            panic!("position locked");
+        }
+        if pos.multiplier_bps as i128 == BPS {
+            return;
+        }
+        pos.multiplier_bps = BPS as u32;
+        write_position(&e, &owner, &token, &pos);
+        e.events().publish((symbol_short!("poke"), owner, token), pos.amount);
+    }
+
+    /// Retira `amount` antes del vencimiento: el dueño recibe `amount` menos la
+    /// penalidad (redondeada hacia arriba) y la penalidad va al pool Recovery.
+    /// Devuelve lo recibido por el dueño.
+    pub fn withdraw_early(e: Env, owner: Address, token: Address, amount: i128) -> i128 {
+        owner.require_auth();
+        let recovery: Address = match e.storage().instance().get(&State::RECOVERY) {
+            Some(r) => r,
+            None => panic!("early exit not configured"),
+        };
+        let pos = debit_position(&e, &owner, &token, amount, &owner);
+        if e.ledger().timestamp() >= pos.lock_until {
+            panic!("position matured, use withdraw");
+        }
+
+        let penalty = penalty_of(&e, amount);
+        let net = amount - penalty;
+        let token_client = TokenClient::new(&e, &token);
+        if penalty > 0 {
+            token_client.transfer(&e.current_contract_address(), &recovery, &penalty);
+        }
+        if net > 0 {
+            token_client.transfer(&e.current_contract_address(), &owner, &net);
+        }
+        surplus(&e, &token);
+
+        e.events().publish((symbol_short!("early"), owner, token), (amount, penalty));
+        net
+    }
+}
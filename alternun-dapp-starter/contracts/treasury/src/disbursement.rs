@@ -0,0 +1,290 @@
+//! Desembolsos M-de-N desde el saldo custodiado de un pool.
+//!
+//! Cada pool puede tener un set de firmantes y un umbral. Un firmante propone un
+//! pago (destino, monto, memo y vencimiento), el resto aprueba y, alcanzado el
+//! umbral antes del vencimiento, cualquiera puede ejecutarlo. La propuesta puede
+//! ser un pago directo, un stream de vesting (`propose_stream`) o la cancelación
+//! de un stream del pool (`propose_cancel_stream`).
+//!
+//! Los firmantes se definen o quitan con `ConfigChange::PoolSigners` vía timelock,
+//! así el admin no puede reemplazarlos en el acto y el guardian alcanza a cancelar.
+
+use soroban_sdk::{
+    contractimpl, contracttype, panic_with_error, symbol_short,
+    token::Client as TokenClient,
+    Address, Env, String, Vec,
+};
+
+use crate::stream::{close_stream, open_stream, read_stream, validate_schedule};
+use crate::{debit_pool, DataKey, Treasury, TreasuryClient, TreasuryError};
+
+const MAX_SIGNERS: u32 = 20;
+
+/// Firmantes de un pool y cuántas aprobaciones hacen falta.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolSigners {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DisbursementStatus {
+    Pending,
+    Executed,
+    Cancelled,
+}
+
+/// Cómo se paga una propuesta aprobada.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Payout {
+    Transfer,              // transferencia inmediata a `to`
+    Stream(u64, u64, u64), // stream hacia `to` con (start, cliff, end)
+    CancelStream(u64),     // cancela el stream con ese id; `amount` queda en 0
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Disbursement {
+    pub id: u64,
+    pub pool: Address,
+    pub token: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub memo: String,
+    pub expires_at: u64,        // timestamp del ledger
+    pub approvals: Vec<Address>,
+    pub status: DisbursementStatus,
+    pub payout: Payout,
+}
+
+pub(crate) fn read_signers(e: &Env, pool: &Address) -> Option<PoolSigners> {
+    e.storage().persistent().get(&DataKey::PoolSigners(pool.clone()))
+}
+
+// Set no vacío, sin repetidos y con umbral alcanzable; o vacío con umbral 0 para
+// quitar el multisig.
+pub(crate) fn validate_signers(e: &Env, cfg: &PoolSigners) {
+    let signers = &cfg.signers;
+    if signers.is_empty() && cfg.threshold == 0 {
+        return;
+    }
+    if signers.is_empty() || signers.len() > MAX_SIGNERS || cfg.threshold == 0 || cfg.threshold > signers.len() {
+        panic_with_error!(e, TreasuryError::InvalidSigners);
+    }
+    for (i, s) in signers.iter().enumerate() {
+        if signers.iter().skip(i + 1).any(|o| o == s) {
+            panic_with_error!(e, TreasuryError::InvalidSigners);
+        }
+    }
+}
+
+// Con firmantes configurados el pool ya no puede usar `claim`; un set vacío los quita.
+pub(crate) fn write_signers(e: &Env, pool: &Address, cfg: &PoolSigners) {
+    let key = DataKey::PoolSigners(pool.clone());
+    if cfg.signers.is_empty() {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, cfg);
+    }
+}
+
+pub(crate) fn has_signers(e: &Env, pool: &Address) -> bool {
+    e.storage().persistent().has(&DataKey::PoolSigners(pool.clone()))
+}
+
+fn require_signer(e: &Env, pool: &Address, signer: &Address) -> PoolSigners {
+    let cfg = match read_signers(e, pool) {
+        Some(cfg) => cfg,
+        None => panic_with_error!(e, TreasuryError::NotSigner),
+    };
+    if !cfg.signers.contains(signer) {
+        panic_with_error!(e, TreasuryError::NotSigner);
+    }
+    signer.require_auth();
+    cfg
+}
+
+fn read_disbursement(e: &Env, id: u64) -> Disbursement {
+    match e.storage().persistent().get(&DataKey::Disbursement(id)) {
+        Some(d) => d,
+        None => panic_with_error!(e, TreasuryError::DisbursementNotFound),
+    }
+}
+
+fn write_disbursement(e: &Env, d: &Disbursement) {
+    e.storage().persistent().set(&DataKey::Disbursement(d.id), d);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn propose(
+    e: &Env,
+    proposer: Address,
+    pool: Address,
+    token: Address,
+    to: Address,
+    amount: i128,
+    memo: String,
+    expires_at: u64,
+    payout: Payout,
+) -> u64 {
+    require_signer(e, &pool, &proposer);
+    if amount <= 0 && !matches!(payout, Payout::CancelStream(_)) {
+        panic_with_error!(e, TreasuryError::InvalidAmount);
+    }
+    if expires_at <= e.ledger().timestamp() {
+        panic_with_error!(e, TreasuryError::Expired);
+    }
+
+    let id = e.storage().instance().get::<_, u64>(&DataKey::DisbursementSeq).unwrap_or(0) + 1;
+    e.storage().instance().set(&DataKey::DisbursementSeq, &id);
+
+    let mut approvals = Vec::new(e);
+    approvals.push_back(proposer);
+    let d = Disbursement {
+        id,
+        pool: pool.clone(),
+        token,
+        to,
+        amount,
+        memo,
+        expires_at,
+        approvals,
+        status: DisbursementStatus::Pending,
+        payout,
+    };
+    write_disbursement(e, &d);
+    e.events().publish((symbol_short!("proposed"), pool), id);
+    id
+}
+
+fn require_open(e: &Env, d: &Disbursement) {
+    if d.status != DisbursementStatus::Pending {
+        panic_with_error!(e, TreasuryError::NotPending);
+    }
+    if e.ledger().timestamp() >= d.expires_at {
+        panic_with_error!(e, TreasuryError::Expired);
+    }
+}
+
+#[contractimpl]
+impl Treasury {
+    pub fn pool_signers(e: Env, pool: Address) -> Option<PoolSigners> {
+        read_signers(&e, &pool)
+    }
+
+    /// Propone pagar `amount` de `token` desde `pool` a `to`. La propuesta cuenta
+    /// como aprobación de `proposer`. Devuelve el id.
+    #[allow(clippy::too_many_arguments)]
+    pub fn propose_disbursement(
+        e: Env,
+        proposer: Address,
+        pool: Address,
+        token: Address,
+        to: Address,
+        amount: i128,
+        memo: String,
+        expires_at: u64,
+    ) -> u64 {
+        propose(&e, proposer, pool, token, to, amount, memo, expires_at, Payout::Transfer)
+    }
+
+    /// Propone un stream de `amount` de `token` desde `pool` hacia `beneficiary`
+    /// entre `start` y `end` con cliff en `cliff`. Al ejecutarse con el umbral de
+    /// firmantes se crea el stream. Devuelve el id de la propuesta.
+    #[allow(clippy::too_many_arguments)]
+    pub fn propose_stream(
+        e: Env,
+        proposer: Address,
+        pool: Address,
+        token: Address,
+        beneficiary: Address,
+        amount: i128,
+        start: u64,
+        cliff: u64,
+        end: u64,
+        memo: String,
+        expires_at: u64,
+    ) -> u64 {
+        validate_schedule(&e, start, cliff, end);
+        propose(&e, proposer, pool, token, beneficiary, amount, memo, expires_at, Payout::Stream(start, cliff, end))
+    }
+
+    /// Propone cancelar el stream `stream_id`, que tiene que salir de un pool con
+    /// firmantes. Al ejecutarse lo no vestido vuelve al pool. Devuelve el id de la
+    /// propuesta.
+    pub fn propose_cancel_stream(e: Env, proposer: Address, stream_id: u64, memo: String, expires_at: u64) -> u64 {
+        let s = read_stream(&e, stream_id);
+        if s.cancelled {
+            panic_with_error!(&e, TreasuryError::StreamCancelled);
+        }
+        propose(&e, proposer, s.pool, s.token, s.beneficiary, 0, memo, expires_at, Payout::CancelStream(stream_id))
+    }
+
+    pub fn approve_disbursement(e: Env, signer: Address, id: u64) {
+        let mut d = read_disbursement(&e, id);
+        require_signer(&e, &d.pool, &signer);
+        require_open(&e, &d);
+        if d.approvals.contains(&signer) {
+            panic_with_error!(&e, TreasuryError::AlreadyApproved);
+        }
+        d.approvals.push_back(signer);
+        write_disbursement(&e, &d);
+    }
+
+    /// Ejecuta una propuesta con aprobaciones suficientes de los firmantes vigentes.
+    pub fn execute_disbursement(e: Env, id: u64) {
+        let mut d = read_disbursement(&e, id);
+        require_open(&e, &d);
+
+        let cfg = match read_signers(&e, &d.pool) {
+            Some(cfg) => cfg,
+            None => panic_with_error!(&e, TreasuryError::ThresholdNotMet),
+        };
+        let valid = d.approvals.iter().filter(|a| cfg.signers.contains(a)).count() as u32;
+        if valid < cfg.threshold {
+            panic_with_error!(&e, TreasuryError::ThresholdNotMet);
+        }
+
+        d.status = DisbursementStatus::Executed;
+        write_disbursement(&e, &d);
+
+        match d.payout {
+            Payout::Transfer => {
+                debit_pool(&e, &d.pool, &d.token, d.amount);
+                TokenClient::new(&e, &d.token).transfer(&e.current_contract_address(), &d.to, &d.amount);
+                e.events().publish((symbol_short!("disbursed"), d.pool, d.token), (id, d.to, d.amount));
+            }
+            Payout::Stream(start, cliff, end) => {
+                // Si `start` ya pasó mientras se juntaban firmas, el stream empieza
+                // al ejecutarse: nunca vesta nada antes de la aprobación
+                let start = start.max(e.ledger().timestamp());
+                let cliff = cliff.max(start);
+                if start >= end {
+                    panic_with_error!(&e, TreasuryError::InvalidSchedule);
+                }
+                open_stream(&e, &d.pool, &d.token, &d.to, d.amount, start, cliff, end);
+            }
+            Payout::CancelStream(stream_id) => {
+                close_stream(&e, stream_id);
+            }
+        }
+    }
+
+    /// Cualquier firmante del pool puede cancelar una propuesta pendiente.
+    pub fn cancel_disbursement(e: Env, signer: Address, id: u64) {
+        let mut d = read_disbursement(&e, id);
+        require_signer(&e, &d.pool, &signer);
+        if d.status != DisbursementStatus::Pending {
+            panic_with_error!(&e, TreasuryError::NotPending);
+        }
+        d.status = DisbursementStatus::Cancelled;
+        write_disbursement(&e, &d);
+    }
+
+    pub fn get_disbursement(e: Env, id: u64) -> Disbursement {
+        read_disbursement(&e, id)
+    }
+}
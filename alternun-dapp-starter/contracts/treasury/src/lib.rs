@@ -1,9 +1,10 @@
 #![no_std]
 
+use alternun_math::{apply_bps, Rounding, BPS};
 use soroban_sdk::{
-    contract, contractimpl,
-    Env, Address, Symbol,
-    symbol_short,
+    contract, contractimpl, contracttype,
+    Env, Address, Symbol, Vec,
+    symbol_short, vec,
     token::Client as TokenClient,
     panic_with_error, contracterror,
 };
@@ -14,12 +15,261 @@ impl State {
     pub const ADDR_P: Symbol = symbol_short!("addr_p"); // projects
     pub const ADDR_R: Symbol = symbol_short!("addr_r"); // recovery
     pub const ADDR_A: Symbol = symbol_short!("addr_a"); // alternun
+    pub const SPLITS: Symbol = symbol_short!("splits"); // Vec<Split>
+    pub const DUST: Symbol   = symbol_short!("dust");   // receptor del redondeo
+    pub const ESCROW: Symbol = symbol_short!("escrow"); // bool: custodia en vez de push
+    pub const TOKENS: Symbol = symbol_short!("tokens"); // Vec<Address> tokens aceptados
+    pub const DELAY: Symbol  = symbol_short!("delay");  // u64: demora del timelock
+    pub const GUARDIAN: Symbol = symbol_short!("guardian"); // puede cancelar cambios encolados
+    pub const PENDING: Symbol = symbol_short!("pending"); // Vec<u64> cambios encolados
 }
 
+// Claves compuestas (persistent)
+#[contracttype]
+pub enum DataKey {
+    Claimable(Address, Address), // (pool, token) -> saldo reclamable
+    Routed(Address, Address),    // (pool, token) -> acumulado ruteado
+    Claimed(Address, Address),   // (pool, token) -> acumulado retirado
+    EpochRouted(Address, Address, u64), // (pool, token, epoch) -> ruteado en el epoch
+    EpochClaimed(Address, Address, u64), // (pool, token, epoch) -> retirado en el epoch
+    PoolSigners(Address),        // pool -> PoolSigners (multisig)
+    DisbursementSeq,             // último id de propuesta
+    Disbursement(u64),           // id -> Disbursement
+    StreamSeq,                   // último id de stream
+    Stream(u64),                 // id -> Stream
+    TokenRule(Address),          // token aceptado -> TokenRule
+    ChangeSeq,                   // último id de cambio encolado
+    Change(u64),                 // id -> PendingChange
+    Source(Address),             // contrato autorizado a llamar `route`
+    Payment(Address, u64),       // (source, referencia) -> PaymentRecord
+}
+
+/// Resumen contable de un pool en un token. Siempre `routed - claimed == claimable`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PoolLedger {
+    pub routed: i128,
+    pub claimed: i128,
+    pub claimable: i128,
+}
+
+// Splits por defecto en bps (100% = 10_000)
+const SPLIT_P_BPS: u32 = 5_000;
+const SPLIT_R_BPS: u32 = 3_000;
+const SPLIT_A_BPS: u32 = 2_000;
+const MAX_SPLITS: u32 = 10;
+// Duración de un epoch contable (30 días); epoch = timestamp / EPOCH_SECS
+const EPOCH_SECS: u64 = 30 * 24 * 60 * 60;
+
 #[contracterror]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum TreasuryError {
     NotAdmin = 1,
+    InvalidSplits = 2,
+    InvalidAmount = 3,
+    NothingToClaim = 4,
+    InsufficientBalance = 5,
+    PoolIsMultisig = 6,
+    InvalidSigners = 7,
+    NotSigner = 8,
+    DisbursementNotFound = 9,
+    NotPending = 10,
+    Expired = 11,
+    AlreadyApproved = 12,
+    ThresholdNotMet = 13,
+    InvalidSchedule = 14,
+    StreamNotFound = 15,
+    StreamCancelled = 16,
+    TokenNotAllowed = 17,
+    BelowMinimum = 18,
+    ChangeNotFound = 19,
+    NotReady = 20,
+    NotGuardian = 21,
+    TooManyPending = 22,
+    UnknownSource = 23,
+    ReferenceMismatch = 24,
+    InvalidDelay = 25,
+}
+
+/// Destino de una porción de lo ruteado, en bps sobre el total.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Split {
+    pub recipient: Address,
+    pub bps: u32,
+}
+
+/// Reglas de ruteo de un token aceptado.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenRule {
+    pub splits: Option<Vec<Split>>, // None = usa la tabla global
+    pub min_amount: i128,           // monto mínimo por route
+}
+
+/// Registro de un `route` ya procesado, por (source, referencia).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentRecord {
+    pub token: Address,
+    pub from: Address,
+    pub amount: i128,
+    pub shares: Vec<(Address, i128)>,
+    pub timestamp: u64,
+}
+
+fn require_admin(e: &Env, admin: &Address) {
+    let stored_admin: Address = e.storage().instance().get(&State::ADMIN).unwrap();
+    if *admin != stored_admin {
+        panic_with_error!(e, TreasuryError::NotAdmin);
+    }
+    admin.require_auth();
+}
+
+fn default_splits(e: &Env, addr_p: &Address, addr_r: &Address, addr_a: &Address) -> Vec<Split> {
+    vec![
+        e,
+        Split { recipient: addr_p.clone(), bps: SPLIT_P_BPS },
+        Split { recipient: addr_r.clone(), bps: SPLIT_R_BPS },
+        Split { recipient: addr_a.clone(), bps: SPLIT_A_BPS },
+    ]
+}
+
+// Tabla no vacía, sin destinos repetidos ni entradas en 0 y que sume exactamente 10_000.
+fn validate_splits(e: &Env, splits: &Vec<Split>) {
+    if splits.is_empty() || splits.len() > MAX_SPLITS {
+        panic_with_error!(e, TreasuryError::InvalidSplits);
+    }
+    let mut total: i128 = 0;
+    for (i, s) in splits.iter().enumerate() {
+        if s.bps == 0 {
+            panic_with_error!(e, TreasuryError::InvalidSplits);
+        }
+        for other in splits.iter().skip(i + 1) {
+            if other.recipient == s.recipient {
+                panic_with_error!(e, TreasuryError::InvalidSplits);
+            }
+        }
+        total += s.bps as i128;
+    }
+    if total != BPS {
+        panic_with_error!(e, TreasuryError::InvalidSplits);
+    }
+}
+
+// Instancias anteriores a la tabla configurable usan el 50/30/20 sobre sus pools.
+fn read_splits(e: &Env) -> Vec<Split> {
+    e.storage().instance().get(&State::SPLITS).unwrap_or_else(|| {
+        let addr_p: Address = e.storage().instance().get(&State::ADDR_P).unwrap();
+        let addr_r: Address = e.storage().instance().get(&State::ADDR_R).unwrap();
+        let addr_a: Address = e.storage().instance().get(&State::ADDR_A).unwrap();
+        default_splits(e, &addr_p, &addr_r, &addr_a)
+    })
+}
+
+fn read_token_rule(e: &Env, token: &Address) -> TokenRule {
+    match e.storage().persistent().get(&DataKey::TokenRule(token.clone())) {
+        Some(rule) => rule,
+        None => panic_with_error!(e, TreasuryError::TokenNotAllowed),
+    }
+}
+
+fn read_tokens(e: &Env) -> Vec<Address> {
+    e.storage().instance().get(&State::TOKENS).unwrap_or(Vec::new(e))
+}
+
+// Por defecto el residuo va al pool Alternun.
+fn read_dust_recipient(e: &Env) -> Address {
+    e.storage().instance().get(&State::DUST)
+        .unwrap_or_else(|| e.storage().instance().get(&State::ADDR_A).unwrap())
+}
+
+// Porciones floor de cada split más el residuo para `dust`. Si `dust` ya está en la
+// tabla se suma a su porción; si no, se agrega como una transferencia más.
+fn compute_shares(e: &Env, splits: &Vec<Split>, dust: &Address, amount: i128) -> Vec<(Address, i128)> {
+    let mut shares = Vec::new(e);
+    let mut distributed: i128 = 0;
+    for s in splits.iter() {
+        let share = apply_bps(amount, s.bps as i128, Rounding::Floor).expect("math overflow");
+        distributed += share;
+        shares.push_back((s.recipient, share));
+    }
+
+    let remainder = amount - distributed;
+    if remainder > 0 {
+        match shares.iter().position(|(r, _)| r == *dust) {
+            Some(i) => {
+                let (r, share) = shares.get(i as u32).unwrap();
+                shares.set(i as u32, (r, share + remainder));
+            }
+            None => shares.push_back((dust.clone(), remainder)),
+        }
+    }
+    shares
+}
+
+fn read_escrow(e: &Env) -> bool {
+    e.storage().instance().get(&State::ESCROW).unwrap_or(false)
+}
+
+fn read_claimable(e: &Env, pool: &Address, token: &Address) -> i128 {
+    e.storage().persistent().get(&DataKey::Claimable(pool.clone(), token.clone())).unwrap_or(0)
+}
+
+fn write_claimable(e: &Env, pool: &Address, token: &Address, amount: i128) {
+    e.storage().persistent().set(&DataKey::Claimable(pool.clone(), token.clone()), &amount);
+}
+
+fn current_epoch(e: &Env) -> u64 {
+    e.ledger().timestamp() / EPOCH_SECS
+}
+
+fn read_amount(e: &Env, key: &DataKey) -> i128 {
+    e.storage().persistent().get(key).unwrap_or(0)
+}
+
+fn add_amount(e: &Env, key: &DataKey, amount: i128) {
+    let prev = read_amount(e, key);
+    e.storage().persistent().set(key, &(prev + amount));
+}
+
+// Acumula lo ruteado a `pool` (total y del epoch actual) y emite el evento.
+fn record_routed(e: &Env, pool: &Address, token: &Address, amount: i128) {
+    let epoch = current_epoch(e);
+    add_amount(e, &DataKey::Routed(pool.clone(), token.clone()), amount);
+    add_amount(e, &DataKey::EpochRouted(pool.clone(), token.clone(), epoch), amount);
+    e.events().publish((symbol_short!("routed"), pool.clone(), token.clone()), (amount, epoch));
+}
+
+// Acumula lo entregado a `pool` (total y del epoch actual) y emite el evento.
+fn record_claimed(e: &Env, pool: &Address, token: &Address, amount: i128) {
+    let epoch = current_epoch(e);
+    add_amount(e, &DataKey::Claimed(pool.clone(), token.clone()), amount);
+    add_amount(e, &DataKey::EpochClaimed(pool.clone(), token.clone(), epoch), amount);
+    e.events().publish((symbol_short!("claimed"), pool.clone(), token.clone()), (amount, epoch));
+}
+
+// Descuenta `amount` del saldo reclamable de `pool` y lo contabiliza como retirado.
+// Quien llama se encarga de transferir los fondos.
+fn debit_pool(e: &Env, pool: &Address, token: &Address, amount: i128) {
+    let balance = read_claimable(e, pool, token);
+    if amount <= 0 || amount > balance {
+        panic_with_error!(e, TreasuryError::InsufficientBalance);
+    }
+    write_claimable(e, pool, token, balance - amount);
+    record_claimed(e, pool, token, amount);
+}
+
+// Devuelve al saldo de `pool` algo que se había descontado con `debit_pool` y no llegó
+// a salir del treasury (p. ej. lo no vestido de un stream cancelado). Se descuenta
+// del retirado del epoch actual, que por eso es neto de devoluciones.
+fn refund_pool(e: &Env, pool: &Address, token: &Address, amount: i128) {
+    let epoch = current_epoch(e);
+    let balance = read_claimable(e, pool, token);
+    write_claimable(e, pool, token, balance + amount);
+    add_amount(e, &DataKey::Claimed(pool.clone(), token.clone()), -amount);
+    add_amount(e, &DataKey::EpochClaimed(pool.clone(), token.clone(), epoch), -amount);
+    e.events().publish((symbol_short!("refunded"), pool.clone(), token.clone()), (amount, epoch));
 }
 
 #[contract]
@@ -37,26 +287,180 @@ impl Treasury {
         e.storage().instance().set(&State::ADDR_P, &addr_p);
         e.storage().instance().set(&State::ADDR_R, &addr_r);
         e.storage().instance().set(&State::ADDR_A, &addr_a);
+        e.storage().instance().set(&State::SPLITS, &default_splits(&e, &addr_p, &addr_r, &addr_a));
+        e.storage().instance().set(&State::DUST, &addr_a);
     }
 
-    /// 🔧 Nuevo: permite actualizar las 3 direcciones de destino (solo admin)
-    pub fn set_pools(e: Env, admin: Address, addr_p: Address, addr_r: Address, addr_a: Address) {
-        let stored_admin: Address = e.storage().instance().get(&State::ADMIN).unwrap();
-        if admin != stored_admin {
-            panic_with_error!(&e, TreasuryError::NotAdmin);
+    // Pools y tablas de splits se cambian vía `queue_change` (ver `timelock`).
+
+    /// Tabla de splits vigente.
+    pub fn splits(e: Env) -> Vec<Split> {
+        read_splits(&e)
+    }
+
+    /// Acepta `token` en `route` (o actualiza su monto mínimo) (solo admin). Un token
+    /// nuevo usa la tabla global; la tabla propia se cambia con
+    /// `ConfigChange::TokenSplits` vía timelock.
+    pub fn set_token_rule(e: Env, admin: Address, token: Address, min_amount: i128) {
+        require_admin(&e, &admin);
+        if min_amount < 0 {
+            panic_with_error!(&e, TreasuryError::InvalidAmount);
         }
-        admin.require_auth();
+        let key = DataKey::TokenRule(token.clone());
+        let splits = e.storage().persistent().get::<_, TokenRule>(&key).and_then(|r| r.splits);
+        e.storage().persistent().set(&key, &TokenRule { splits, min_amount });
 
-        e.storage().instance().set(&State::ADDR_P, &addr_p);
-        e.storage().instance().set(&State::ADDR_R, &addr_r);
-        e.storage().instance().set(&State::ADDR_A, &addr_a);
+        let mut tokens = read_tokens(&e);
+        if !tokens.contains(&token) {
+            tokens.push_back(token);
+            e.storage().instance().set(&State::TOKENS, &tokens);
+        }
     }
 
-    /// Divide `amount` en 50/30/20 y transfiere con el token estándar.
-    pub fn route(e: Env, token: Address, from: Address, amount: i128) {
-        let addr_p: Address = e.storage().instance().get(&State::ADDR_P).unwrap();
-        let addr_r: Address = e.storage().instance().get(&State::ADDR_R).unwrap();
-        let addr_a: Address = e.storage().instance().get(&State::ADDR_A).unwrap();
+    /// Deja de aceptar `token` en `route` (solo admin). Los saldos ya custodiados
+    /// siguen siendo reclamables.
+    pub fn remove_token(e: Env, admin: Address, token: Address) {
+        require_admin(&e, &admin);
+        e.storage().persistent().remove(&DataKey::TokenRule(token.clone()));
+        let mut tokens = read_tokens(&e);
+        if let Some(i) = tokens.first_index_of(&token) {
+            tokens.remove(i);
+            e.storage().instance().set(&State::TOKENS, &tokens);
+        }
+    }
+
+    pub fn allowed_tokens(e: Env) -> Vec<Address> {
+        read_tokens(&e)
+    }
+
+    pub fn token_rule(e: Env, token: Address) -> Option<TokenRule> {
+        e.storage().persistent().get(&DataKey::TokenRule(token))
+    }
+
+    /// Habilita o deshabilita un contrato (minter, bonding curve, ...) como origen
+    /// de `route` (solo admin).
+    pub fn set_source(e: Env, admin: Address, source: Address, allowed: bool) {
+        require_admin(&e, &admin);
+        let key = DataKey::Source(source);
+        if allowed {
+            e.storage().persistent().set(&key, &true);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+    }
+
+    pub fn is_source(e: Env, source: Address) -> bool {
+        e.storage().persistent().has(&DataKey::Source(source))
+    }
+
+    /// Pago ruteado por `source` con la referencia dada, si existe.
+    pub fn get_payment(e: Env, source: Address, reference: u64) -> Option<PaymentRecord> {
+        e.storage().persistent().get(&DataKey::Payment(source, reference))
+    }
+
+    // El receptor del residuo se cambia con `ConfigChange::Dust` vía timelock.
+
+    pub fn dust_recipient(e: Env) -> Address {
+        read_dust_recipient(&e)
+    }
+
+    // El modo escrow se activa o desactiva con `ConfigChange::Escrow` vía timelock.
+    // En escrow el treasury custodia lo ruteado y cada pool lo retira con `claim`.
+
+    pub fn escrow(e: Env) -> bool {
+        read_escrow(&e)
+    }
+
+    /// Saldo de `token` pendiente de reclamar por `pool`.
+    pub fn claimable(e: Env, pool: Address, token: Address) -> i128 {
+        read_claimable(&e, &pool, &token)
+    }
+
+    /// Retira todo el saldo reclamable de `pool` en `token` hacia el propio pool.
+    /// Los pools con firmantes configurados sólo gastan vía propuestas multisig.
+    pub fn claim(e: Env, pool: Address, token: Address) -> i128 {
+        pool.require_auth();
+        if disbursement::has_signers(&e, &pool) {
+            panic_with_error!(&e, TreasuryError::PoolIsMultisig);
+        }
+
+        let amount = read_claimable(&e, &pool, &token);
+        if amount <= 0 {
+            panic_with_error!(&e, TreasuryError::NothingToClaim);
+        }
+        debit_pool(&e, &pool, &token, amount);
+
+        TokenClient::new(&e, &token).transfer(&e.current_contract_address(), &pool, &amount);
+        amount
+    }
+
+    /// Acumulados de `pool` en `token`: ruteado, retirado y pendiente.
+    pub fn pool_ledger(e: Env, pool: Address, token: Address) -> PoolLedger {
+        PoolLedger {
+            routed: read_amount(&e, &DataKey::Routed(pool.clone(), token.clone())),
+            claimed: read_amount(&e, &DataKey::Claimed(pool.clone(), token.clone())),
+            claimable: read_claimable(&e, &pool, &token),
+        }
+    }
+
+    pub fn total_routed(e: Env, pool: Address, token: Address) -> i128 {
+        read_amount(&e, &DataKey::Routed(pool, token))
+    }
+
+    pub fn total_claimed(e: Env, pool: Address, token: Address) -> i128 {
+        read_amount(&e, &DataKey::Claimed(pool, token))
+    }
+
+    /// Ruteado a `pool` en `token` durante `epoch` (ver `current_epoch`).
+    pub fn routed_in_epoch(e: Env, pool: Address, token: Address, epoch: u64) -> i128 {
+        read_amount(&e, &DataKey::EpochRouted(pool, token, epoch))
+    }
+
+    /// Retirado por `pool` en `token` durante `epoch`, neto de devoluciones de
+    /// streams cancelados en ese epoch.
+    pub fn claimed_in_epoch(e: Env, pool: Address, token: Address, epoch: u64) -> i128 {
+        read_amount(&e, &DataKey::EpochClaimed(pool, token, epoch))
+    }
+
+    pub fn current_epoch(e: Env) -> u64 {
+        current_epoch(&e)
+    }
+
+    /// Divide `amount` según la tabla de splits del token (o la global) y transfiere
+    /// con el token estándar. Sólo acepta tokens con regla y montos >= su mínimo.
+    /// Cada porción se redondea hacia abajo y el residuo va al receptor de dust, así
+    /// que siempre se distribuye `amount` completo. En modo escrow el total entra al
+    /// treasury y cada porción se acredita como saldo reclamable del pool; fuera de
+    /// escrow la porción se contabiliza como ruteada y retirada a la vez.
+    ///
+    /// Sólo lo llaman contratos registrados con `set_source`, con una `reference`
+    /// propia (p. ej. el seq del recibo de mint). Cada (source, reference) se registra
+    /// una vez: repetir la llamada con los mismos datos devuelve las porciones ya
+    /// registradas sin mover fondos, y con datos distintos falla.
+    /// Devuelve `(destino, monto)` de cada porción.
+    pub fn route(e: Env, source: Address, token: Address, from: Address, amount: i128, reference: u64) -> Vec<(Address, i128)> {
+        if !e.storage().persistent().has(&DataKey::Source(source.clone())) {
+            panic_with_error!(&e, TreasuryError::UnknownSource);
+        }
+        source.require_auth();
+        if amount < 0 {
+            panic_with_error!(&e, TreasuryError::InvalidAmount);
+        }
+
+        let payment_key = DataKey::Payment(source.clone(), reference);
+        if let Some(prev) = e.storage().persistent().get::<_, PaymentRecord>(&payment_key) {
+            if prev.token != token || prev.from != from || prev.amount != amount {
+                panic_with_error!(&e, TreasuryError::ReferenceMismatch);
+            }
+            return prev.shares;
+        }
+
+        let rule = read_token_rule(&e, &token);
+        if amount < rule.min_amount {
+            panic_with_error!(&e, TreasuryError::BelowMinimum);
+        }
+        let splits = rule.splits.unwrap_or_else(|| read_splits(&e));
+        let routed = compute_shares(&e, &splits, &read_dust_recipient(&e), amount);
 
         // Cliente del contrato token
         let token_client = TokenClient::new(&e, &token);
@@ -64,14 +468,33 @@ impl Treasury {
         // Autorización del `from`
         from.require_auth();
 
-        // Cálculos
-        let p = amount * 50 / 100;
-        let r = amount * 30 / 100;
-        let a = amount * 20 / 100;
+        if read_escrow(&e) {
+            token_client.transfer(&from, &e.current_contract_address(), &amount);
+            for (pool, share) in routed.iter() {
+                let prev = read_claimable(&e, &pool, &token);
+                write_claimable(&e, &pool, &token, prev + share);
+                record_routed(&e, &pool, &token, share);
+            }
+        } else {
+            for (recipient, share) in routed.iter() {
+                token_client.transfer(&from, &recipient, &share);
+                record_routed(&e, &recipient, &token, share);
+                record_claimed(&e, &recipient, &token, share);
+            }
+        }
 
-        // Transferencias
-        token_client.transfer(&from, &addr_p, &p);
-        token_client.transfer(&from, &addr_r, &r);
-        token_client.transfer(&from, &addr_a, &a);
+        let record = PaymentRecord { token, from, amount, shares: routed.clone(), timestamp: e.ledger().timestamp() };
+        e.storage().persistent().set(&payment_key, &record);
+        e.events().publish((symbol_short!("payment"), source), (reference, amount));
+        routed
     }
 }
+
+mod disbursement;
+mod stream;
+mod timelock;
+mod test;
+
+pub use disbursement::{Disbursement, DisbursementStatus, Payout, PoolSigners};
+pub use stream::Stream;
+pub use timelock::{ConfigChange, PendingChange};
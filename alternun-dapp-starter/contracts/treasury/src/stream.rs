@@ -0,0 +1,184 @@
+//! Streams de vesting financiados con el saldo custodiado de un pool.
+//!
+//! Al crear el stream el monto sale del saldo del pool y queda reservado en el
+//! treasury. Vesta linealmente entre `start` y `end`, sin nada retirable antes de
+//! `cliff`. Cancelar devuelve al pool lo aún no vestido.
+//!
+//! El admin sólo crea y cancela streams de pools sin firmantes; en los pools
+//! multisig ambas cosas pasan por propuestas con el umbral de sus firmantes
+//! (`propose_stream` y `propose_cancel_stream`, ver `disbursement`).
+
+use alternun_math::{mul_div, Rounding};
+use soroban_sdk::{
+    contractimpl, contracttype, panic_with_error, symbol_short,
+    token::Client as TokenClient,
+    Address, Env,
+};
+
+use crate::disbursement::has_signers;
+use crate::{debit_pool, refund_pool, require_admin, DataKey, Treasury, TreasuryClient, TreasuryError};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stream {
+    pub id: u64,
+    pub pool: Address,
+    pub token: Address,
+    pub beneficiary: Address,
+    pub total: i128,          // monto del stream; al cancelar queda en lo vestido
+    pub start: u64,           // timestamps del ledger
+    pub cliff: u64,
+    pub end: u64,
+    pub withdrawn: i128,
+    pub cancelled: bool,
+}
+
+pub(crate) fn read_stream(e: &Env, id: u64) -> Stream {
+    match e.storage().persistent().get(&DataKey::Stream(id)) {
+        Some(s) => s,
+        None => panic_with_error!(e, TreasuryError::StreamNotFound),
+    }
+}
+
+fn write_stream(e: &Env, s: &Stream) {
+    e.storage().persistent().set(&DataKey::Stream(s.id), s);
+}
+
+// Monto vestido a `now` (redondeo hacia abajo).
+fn vested_at(s: &Stream, now: u64) -> i128 {
+    if s.cancelled || now >= s.end {
+        return s.total;
+    }
+    if now < s.cliff {
+        return 0;
+    }
+    let elapsed = (now - s.start) as i128;
+    let duration = (s.end - s.start) as i128;
+    mul_div(s.total, elapsed, duration, Rounding::Floor).expect("math overflow")
+}
+
+// El stream no puede empezar en el pasado (vestiría todo de una) y el cliff cae
+// dentro del plazo.
+pub(crate) fn validate_schedule(e: &Env, start: u64, cliff: u64, end: u64) {
+    if start < e.ledger().timestamp() || start >= end || cliff < start || cliff > end {
+        panic_with_error!(e, TreasuryError::InvalidSchedule);
+    }
+}
+
+// Descuenta `amount` del saldo de `pool` y lo reserva en un stream nuevo. Devuelve el id.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn open_stream(
+    e: &Env,
+    pool: &Address,
+    token: &Address,
+    beneficiary: &Address,
+    amount: i128,
+    start: u64,
+    cliff: u64,
+    end: u64,
+) -> u64 {
+    if amount <= 0 {
+        panic_with_error!(e, TreasuryError::InvalidAmount);
+    }
+    validate_schedule(e, start, cliff, end);
+    debit_pool(e, pool, token, amount);
+
+    let id = e.storage().instance().get::<_, u64>(&DataKey::StreamSeq).unwrap_or(0) + 1;
+    e.storage().instance().set(&DataKey::StreamSeq, &id);
+
+    let s = Stream {
+        id,
+        pool: pool.clone(),
+        token: token.clone(),
+        beneficiary: beneficiary.clone(),
+        total: amount,
+        start,
+        cliff,
+        end,
+        withdrawn: 0,
+        cancelled: false,
+    };
+    write_stream(e, &s);
+    e.events().publish((symbol_short!("stream"), pool.clone()), (id, amount));
+    id
+}
+
+// Congela el stream en lo vestido a hoy y devuelve el resto al pool. Devuelve
+// lo devuelto.
+pub(crate) fn close_stream(e: &Env, id: u64) -> i128 {
+    let mut s = read_stream(e, id);
+    if s.cancelled {
+        panic_with_error!(e, TreasuryError::StreamCancelled);
+    }
+
+    let vested = vested_at(&s, e.ledger().timestamp());
+    let unvested = s.total - vested;
+    s.total = vested;
+    s.cancelled = true;
+    write_stream(e, &s);
+
+    if unvested > 0 {
+        refund_pool(e, &s.pool, &s.token, unvested);
+    }
+    unvested
+}
+
+#[contractimpl]
+impl Treasury {
+    /// Crea un stream de `amount` de `token` desde el saldo de `pool` hacia
+    /// `beneficiary` (solo admin y sólo para pools sin firmantes). Devuelve el id.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stream(
+        e: Env,
+        admin: Address,
+        pool: Address,
+        token: Address,
+        beneficiary: Address,
+        amount: i128,
+        start: u64,
+        cliff: u64,
+        end: u64,
+    ) -> u64 {
+        require_admin(&e, &admin);
+        if has_signers(&e, &pool) {
+            panic_with_error!(&e, TreasuryError::PoolIsMultisig);
+        }
+        open_stream(&e, &pool, &token, &beneficiary, amount, start, cliff, end)
+    }
+
+    /// Transfiere al beneficiario todo lo vestido y aún no retirado.
+    pub fn withdraw_vested(e: Env, id: u64) -> i128 {
+        let mut s = read_stream(&e, id);
+        s.beneficiary.require_auth();
+
+        let amount = vested_at(&s, e.ledger().timestamp()) - s.withdrawn;
+        if amount <= 0 {
+            panic_with_error!(&e, TreasuryError::NothingToClaim);
+        }
+        s.withdrawn += amount;
+        write_stream(&e, &s);
+
+        TokenClient::new(&e, &s.token).transfer(&e.current_contract_address(), &s.beneficiary, &amount);
+        amount
+    }
+
+    /// Cancela el stream (solo admin y sólo para pools sin firmantes): lo vestido
+    /// sigue siendo retirable por el beneficiario y el resto vuelve al saldo del
+    /// pool.
+    pub fn cancel_stream(e: Env, admin: Address, id: u64) -> i128 {
+        require_admin(&e, &admin);
+        if has_signers(&e, &read_stream(&e, id).pool) {
+            panic_with_error!(&e, TreasuryError::PoolIsMultisig);
+        }
+        close_stream(&e, id)
+    }
+
+    pub fn get_stream(e: Env, id: u64) -> Stream {
+        read_stream(&e, id)
+    }
+
+    /// Monto vestido hasta ahora (incluye lo ya retirado; ver `Stream::withdrawn`).
+    pub fn vested_amount(e: Env, id: u64) -> i128 {
+        vested_at(&read_stream(&e, id), e.ledger().timestamp())
+    }
+}
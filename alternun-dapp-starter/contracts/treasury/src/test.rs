@@ -0,0 +1,522 @@
+#![cfg(test)]
+
+extern crate std;
+
+use super::*;
+
+use ::proptest::prelude::*;
+use soroban_sdk::testutils::{Address as _, Ledger};
+use soroban_sdk::token::Client as TokenClient;
+use soroban_sdk::token::StellarAssetClient as TokenAdminClient;
+use soroban_sdk::{vec, Address, Env, String};
+use std::cell::Cell;
+
+struct Setup<'a> {
+    env: Env,
+    admin: Address,
+    pools: [Address; 3],
+    from: Address,
+    source: Address,
+    next_ref: Cell<u64>,
+    token: TokenClient<'a>,
+    treasury: TreasuryClient<'a>,
+}
+
+fn setup<'a>() -> Setup<'a> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let pools = [Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    let from = Address::generate(&env);
+
+    let sac = env.register_stellar_asset_contract_v2(admin.clone());
+    let token = TokenClient::new(&env, &sac.address());
+    TokenAdminClient::new(&env, &sac.address()).mint(&from, &i128::MAX);
+
+    let treasury = TreasuryClient::new(&env, &env.register_contract(None, Treasury));
+    treasury.init(&admin, &pools[0], &pools[1], &pools[2]);
+    treasury.set_token_rule(&admin, &token.address, &0);
+    let source = Address::generate(&env);
+    treasury.set_source(&admin, &source, &true);
+
+    Setup { env, admin, pools, from, source, next_ref: Cell::new(0), token, treasury }
+}
+
+// Encola `change`, espera la demora y lo ejecuta.
+fn apply_change(s: &Setup, change: ConfigChange) {
+    let id = s.treasury.queue_change(&s.admin, &change);
+    let eta = s.treasury.get_change(&id).unwrap().eta;
+    s.env.ledger().set_timestamp(eta);
+    s.treasury.execute_change(&id);
+}
+
+fn next_ref(s: &Setup) -> u64 {
+    s.next_ref.set(s.next_ref.get() + 1);
+    s.next_ref.get()
+}
+
+// `route` desde el source registrado con una referencia nueva.
+fn route(s: &Setup, amount: i128) -> Vec<(Address, i128)> {
+    s.treasury.route(&s.source, &s.token.address, &s.from, &amount, &next_ref(s))
+}
+
+fn balances(s: &Setup, extra: &[&Address]) -> i128 {
+    s.pools.iter().chain(extra.iter().copied()).map(|a| s.token.balance(a)).sum()
+}
+
+#[test]
+fn default_split_sends_dust_to_alternun() {
+    let s = setup();
+    let routed = route(&s, 1_001);
+
+    assert_eq!(s.token.balance(&s.pools[0]), 500);
+    assert_eq!(s.token.balance(&s.pools[1]), 300);
+    assert_eq!(s.token.balance(&s.pools[2]), 201);
+    assert_eq!(routed.len(), 3);
+    assert_eq!(balances(&s, &[]), 1_001);
+}
+
+#[test]
+fn dust_recipient_outside_table_gets_extra_transfer() {
+    let s = setup();
+    let dust = Address::generate(&s.env);
+    apply_change(&s, ConfigChange::Dust(dust.clone()));
+    apply_change(
+        &s,
+        ConfigChange::Splits(vec![
+            &s.env,
+            Split { recipient: s.pools[0].clone(), bps: 3_333 },
+            Split { recipient: s.pools[1].clone(), bps: 3_333 },
+            Split { recipient: s.pools[2].clone(), bps: 3_334 },
+        ]),
+    );
+
+    let routed = route(&s, 10);
+
+    assert_eq!(routed.len(), 4);
+    assert_eq!(routed.get(3).unwrap(), (dust.clone(), 1));
+    assert_eq!(balances(&s, &[&dust]), 10);
+}
+
+#[test]
+fn exact_amount_leaves_no_dust() {
+    let s = setup();
+    let routed = route(&s, 10_000);
+
+    assert_eq!(routed.len(), 3);
+    assert_eq!(s.token.balance(&s.pools[2]), 2_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn splits_must_sum_to_bps() {
+    let s = setup();
+    s.treasury.queue_change(
+        &s.admin,
+        &ConfigChange::Splits(vec![
+            &s.env,
+            Split { recipient: s.pools[0].clone(), bps: 5_000 },
+            Split { recipient: s.pools[1].clone(), bps: 4_999 },
+        ]),
+    );
+}
+
+#[test]
+fn escrow_credits_pools_and_claim_pays_out() {
+    let s = setup();
+    apply_change(&s, ConfigChange::Escrow(true));
+    route(&s, 1_001);
+
+    assert_eq!(s.token.balance(&s.treasury.address), 1_001);
+    assert_eq!(s.treasury.claimable(&s.pools[0], &s.token.address), 500);
+    assert_eq!(s.treasury.claimable(&s.pools[2], &s.token.address), 201);
+
+    assert_eq!(s.treasury.claim(&s.pools[0], &s.token.address), 500);
+    assert_eq!(s.token.balance(&s.pools[0]), 500);
+    assert_eq!(s.treasury.claimable(&s.pools[0], &s.token.address), 0);
+    assert_eq!(s.token.balance(&s.treasury.address), 501);
+}
+
+#[test]
+fn ledger_tracks_routed_and_claimed_per_pool() {
+    let s = setup();
+    apply_change(&s, ConfigChange::Escrow(true));
+    route(&s, 1_000);
+    route(&s, 2_000);
+    s.treasury.claim(&s.pools[1], &s.token.address);
+    route(&s, 1_000);
+
+    let ledger = s.treasury.pool_ledger(&s.pools[1], &s.token.address);
+    assert_eq!(ledger, PoolLedger { routed: 1_200, claimed: 900, claimable: 300 });
+
+    let epoch = s.treasury.current_epoch();
+    assert_eq!(s.treasury.routed_in_epoch(&s.pools[0], &s.token.address, &epoch), 2_000);
+    assert_eq!(s.treasury.routed_in_epoch(&s.pools[0], &s.token.address, &(epoch + 1)), 0);
+
+    // Fuera de escrow lo ruteado se entrega en el acto
+    apply_change(&s, ConfigChange::Escrow(false));
+    route(&s, 100);
+    let ledger = s.treasury.pool_ledger(&s.pools[2], &s.token.address);
+    assert_eq!(ledger, PoolLedger { routed: 820, claimed: 20, claimable: 800 });
+}
+
+#[test]
+fn epoch_totals_track_routed_and_claimed() {
+    let s = setup();
+    apply_change(&s, ConfigChange::Escrow(true));
+    let pool = &s.pools[0];
+    let epoch = s.treasury.current_epoch();
+
+    route(&s, 1_000);
+    s.treasury.claim(pool, &s.token.address);
+    route(&s, 400);
+
+    // Un epoch después
+    s.env.ledger().set_timestamp(s.env.ledger().timestamp() + 30 * 24 * 60 * 60);
+    assert_eq!(s.treasury.current_epoch(), epoch + 1);
+    route(&s, 600);
+    s.treasury.claim(pool, &s.token.address);
+
+    assert_eq!(s.treasury.routed_in_epoch(pool, &s.token.address, &epoch), 700);
+    assert_eq!(s.treasury.claimed_in_epoch(pool, &s.token.address, &epoch), 500);
+    assert_eq!(s.treasury.routed_in_epoch(pool, &s.token.address, &(epoch + 1)), 300);
+    assert_eq!(s.treasury.claimed_in_epoch(pool, &s.token.address, &(epoch + 1)), 500);
+    assert_eq!(s.treasury.total_claimed(pool, &s.token.address), 1_000);
+}
+
+#[test]
+fn min_delay_stays_within_bounds() {
+    let s = setup();
+    for secs in [0, 60 * 60, 31 * 24 * 60 * 60, u64::MAX] {
+        assert_eq!(
+            s.treasury.try_queue_change(&s.admin, &ConfigChange::MinDelay(secs)),
+            Err(Ok(TreasuryError::InvalidDelay.into()))
+        );
+    }
+
+    let week = 7 * 24 * 60 * 60;
+    apply_change(&s, ConfigChange::MinDelay(week));
+    assert_eq!(s.treasury.min_delay(), week);
+    let now = s.env.ledger().timestamp();
+    let id = s.treasury.queue_change(&s.admin, &ConfigChange::Escrow(true));
+    assert_eq!(s.treasury.get_change(&id).unwrap().eta, now + week);
+}
+
+#[test]
+fn multisig_disbursement_needs_threshold() {
+    let s = setup();
+    apply_change(&s, ConfigChange::Escrow(true));
+    route(&s, 1_000);
+
+    let pool = s.pools[0].clone();
+    let signers = [Address::generate(&s.env), Address::generate(&s.env), Address::generate(&s.env)];
+    let cfg = PoolSigners { signers: vec![&s.env, signers[0].clone(), signers[1].clone(), signers[2].clone()], threshold: 2 };
+    apply_change(&s, ConfigChange::PoolSigners(pool.clone(), cfg));
+
+    let payee = Address::generate(&s.env);
+    let memo = String::from_str(&s.env, "grant #1");
+    let expires_at = s.env.ledger().timestamp() + 1_000;
+    let id = s.treasury.propose_disbursement(&signers[0], &pool, &s.token.address, &payee, &400, &memo, &expires_at);
+
+    assert!(s.treasury.try_execute_disbursement(&id).is_err());
+    s.treasury.approve_disbursement(&signers[2], &id);
+    s.treasury.execute_disbursement(&id);
+
+    assert_eq!(s.token.balance(&payee), 400);
+    assert_eq!(s.treasury.claimable(&pool, &s.token.address), 100);
+    assert_eq!(s.treasury.get_disbursement(&id).status, DisbursementStatus::Executed);
+    // Con firmantes el pool ya no puede retirar por su cuenta
+    assert_eq!(s.treasury.try_claim(&pool, &s.token.address), Err(Ok(TreasuryError::PoolIsMultisig.into())));
+}
+
+#[test]
+fn pool_signers_change_only_through_timelock() {
+    let s = setup();
+    apply_change(&s, ConfigChange::Escrow(true));
+    route(&s, 1_000);
+    let pool = s.pools[0].clone();
+    let signers = vec![&s.env, Address::generate(&s.env), Address::generate(&s.env)];
+    apply_change(&s, ConfigChange::PoolSigners(pool.clone(), PoolSigners { signers, threshold: 2 }));
+
+    // El admin no puede ponerse como único firmante en el acto: queda encolado
+    let takeover = PoolSigners { signers: vec![&s.env, s.admin.clone()], threshold: 1 };
+    let id = s.treasury.queue_change(&s.admin, &ConfigChange::PoolSigners(pool.clone(), takeover));
+    assert_eq!(s.treasury.try_execute_change(&id), Err(Ok(TreasuryError::NotReady.into())));
+    assert_eq!(s.treasury.pool_signers(&pool).unwrap().threshold, 2);
+    s.treasury.cancel_change(&s.admin, &id);
+
+    let bad = PoolSigners { signers: vec![&s.env, s.admin.clone()], threshold: 2 };
+    assert_eq!(
+        s.treasury.try_queue_change(&s.admin, &ConfigChange::PoolSigners(pool.clone(), bad)),
+        Err(Ok(TreasuryError::InvalidSigners.into()))
+    );
+
+    // Quitar los firmantes también pasa por el timelock
+    apply_change(&s, ConfigChange::PoolSigners(pool.clone(), PoolSigners { signers: Vec::new(&s.env), threshold: 0 }));
+    assert_eq!(s.treasury.pool_signers(&pool), None);
+    assert_eq!(s.treasury.claim(&pool, &s.token.address), 500);
+}
+
+#[test]
+fn stream_vests_linearly_and_cancel_refunds_pool() {
+    let s = setup();
+    apply_change(&s, ConfigChange::Escrow(true));
+    route(&s, 2_000);
+
+    let pool = s.pools[0].clone();
+    let beneficiary = Address::generate(&s.env);
+    let t0 = s.env.ledger().timestamp();
+    let id = s.treasury.create_stream(&s.admin, &pool, &s.token.address, &beneficiary, &1_000, &(t0 + 100), &(t0 + 200), &(t0 + 1_100));
+    assert_eq!(s.treasury.claimable(&pool, &s.token.address), 0);
+
+    s.env.ledger().set_timestamp(t0 + 150);
+    assert_eq!(s.treasury.vested_amount(&id), 0);
+
+    s.env.ledger().set_timestamp(t0 + 600);
+    assert_eq!(s.treasury.withdraw_vested(&id), 500);
+    assert_eq!(s.token.balance(&beneficiary), 500);
+
+    s.env.ledger().set_timestamp(t0 + 850);
+    assert_eq!(s.treasury.cancel_stream(&s.admin, &id), 250);
+    assert_eq!(s.treasury.claimable(&pool, &s.token.address), 250);
+
+    s.env.ledger().set_timestamp(t0 + 2_000);
+    assert_eq!(s.treasury.withdraw_vested(&id), 250);
+    let stream = s.treasury.get_stream(&id);
+    assert_eq!((stream.total, stream.withdrawn), (750, 750));
+
+    let ledger = s.treasury.pool_ledger(&pool, &s.token.address);
+    assert_eq!(ledger.routed - ledger.claimed, ledger.claimable);
+}
+
+#[test]
+fn streams_from_multisig_pools_need_signer_threshold() {
+    let s = setup();
+    apply_change(&s, ConfigChange::Escrow(true));
+    route(&s, 2_000);
+    let pool = s.pools[0].clone();
+    let signers = [Address::generate(&s.env), Address::generate(&s.env)];
+    let cfg = PoolSigners { signers: vec![&s.env, signers[0].clone(), signers[1].clone()], threshold: 2 };
+    apply_change(&s, ConfigChange::PoolSigners(pool.clone(), cfg));
+
+    let now = s.env.ledger().timestamp();
+    let beneficiary = Address::generate(&s.env);
+    // Una sola firma de admin no alcanza para vaciar un pool multisig
+    assert_eq!(
+        s.treasury.try_create_stream(&s.admin, &pool, &s.token.address, &beneficiary, &1_000, &now, &now, &(now + 1)),
+        Err(Ok(TreasuryError::PoolIsMultisig.into()))
+    );
+
+    let memo = String::from_str(&s.env, "vesting");
+    let id = s.treasury.propose_stream(
+        &signers[0], &pool, &s.token.address, &beneficiary, &1_000, &(now + 100), &(now + 100), &(now + 1_100), &memo, &(now + 50),
+    );
+    assert_eq!(s.treasury.try_execute_disbursement(&id), Err(Ok(TreasuryError::ThresholdNotMet.into())));
+    s.treasury.approve_disbursement(&signers[1], &id);
+    s.treasury.execute_disbursement(&id);
+
+    assert_eq!(s.treasury.claimable(&pool, &s.token.address), 0);
+    let stream = s.treasury.get_stream(&1);
+    assert_eq!((stream.beneficiary, stream.total, stream.start, stream.end), (beneficiary, 1_000, now + 100, now + 1_100));
+    assert_eq!(s.treasury.vested_amount(&1), 0);
+}
+
+#[test]
+fn multisig_streams_cancel_only_with_signer_threshold() {
+    let s = setup();
+    apply_change(&s, ConfigChange::Escrow(true));
+    route(&s, 2_000);
+    let pool = s.pools[0].clone();
+    let signers = [Address::generate(&s.env), Address::generate(&s.env)];
+    let cfg = PoolSigners { signers: vec![&s.env, signers[0].clone(), signers[1].clone()], threshold: 2 };
+    apply_change(&s, ConfigChange::PoolSigners(pool.clone(), cfg));
+
+    let now = s.env.ledger().timestamp();
+    let beneficiary = Address::generate(&s.env);
+    let memo = String::from_str(&s.env, "vesting");
+    let id = s.treasury.propose_stream(
+        &signers[0], &pool, &s.token.address, &beneficiary, &1_000, &now, &now, &(now + 1_000), &memo, &(now + 50),
+    );
+    s.treasury.approve_disbursement(&signers[1], &id);
+    s.treasury.execute_disbursement(&id);
+    let stream_id = 1;
+
+    // El admin solo no puede deshacer lo que aprobaron los firmantes
+    s.env.ledger().set_timestamp(now + 250);
+    assert_eq!(s.treasury.try_cancel_stream(&s.admin, &stream_id), Err(Ok(TreasuryError::PoolIsMultisig.into())));
+
+    let memo = String::from_str(&s.env, "cancel");
+    let cancel = s.treasury.propose_cancel_stream(&signers[1], &stream_id, &memo, &(now + 500));
+    assert_eq!(s.treasury.try_execute_disbursement(&cancel), Err(Ok(TreasuryError::ThresholdNotMet.into())));
+    s.treasury.approve_disbursement(&signers[0], &cancel);
+    s.treasury.execute_disbursement(&cancel);
+
+    let stream = s.treasury.get_stream(&stream_id);
+    assert!(stream.cancelled);
+    assert_eq!(stream.total, 250);
+    assert_eq!(s.treasury.claimable(&pool, &s.token.address), 750);
+    assert_eq!(s.treasury.withdraw_vested(&stream_id), 250);
+
+    // Un stream ya cancelado no admite otra propuesta
+    assert_eq!(
+        s.treasury.try_propose_cancel_stream(&signers[0], &stream_id, &memo, &(now + 500)),
+        Err(Ok(TreasuryError::StreamCancelled.into()))
+    );
+}
+
+#[test]
+fn stream_cannot_start_in_the_past() {
+    let s = setup();
+    apply_change(&s, ConfigChange::Escrow(true));
+    route(&s, 2_000);
+    s.env.ledger().set_timestamp(1_000);
+
+    let beneficiary = Address::generate(&s.env);
+    assert_eq!(
+        s.treasury.try_create_stream(&s.admin, &s.pools[0], &s.token.address, &beneficiary, &1_000, &0, &0, &1),
+        Err(Ok(TreasuryError::InvalidSchedule.into()))
+    );
+    assert_eq!(s.treasury.claimable(&s.pools[0], &s.token.address), 1_000);
+}
+
+#[test]
+fn route_enforces_token_whitelist_and_rules() {
+    let s = setup();
+    let other = s.env.register_stellar_asset_contract_v2(s.admin.clone()).address();
+    assert_eq!(
+        s.treasury.try_route(&s.source, &other, &s.from, &100, &next_ref(&s)),
+        Err(Ok(TreasuryError::TokenNotAllowed.into()))
+    );
+
+    let table = vec![&s.env, Split { recipient: s.pools[1].clone(), bps: 10_000 }];
+    s.treasury.set_token_rule(&s.admin, &s.token.address, &50);
+    apply_change(&s, ConfigChange::TokenSplits(s.token.address.clone(), Some(table)));
+    assert_eq!(
+        s.treasury.try_route(&s.source, &s.token.address, &s.from, &49, &next_ref(&s)),
+        Err(Ok(TreasuryError::BelowMinimum.into()))
+    );
+
+    route(&s, 50);
+    assert_eq!(s.token.balance(&s.pools[1]), 50);
+
+    s.treasury.remove_token(&s.admin, &s.token.address);
+    assert_eq!(s.treasury.allowed_tokens().len(), 0);
+}
+
+#[test]
+fn pool_changes_wait_for_timelock_and_guardian_can_cancel() {
+    let s = setup();
+    let guardian = Address::generate(&s.env);
+    apply_change(&s, ConfigChange::Guardian(guardian.clone()));
+
+    let new_pools = [Address::generate(&s.env), Address::generate(&s.env), Address::generate(&s.env)];
+    let change = ConfigChange::Pools(new_pools[0].clone(), new_pools[1].clone(), new_pools[2].clone());
+    let id = s.treasury.queue_change(&s.admin, &change);
+    let pending = s.treasury.pending_changes();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending.get(0).unwrap().change, change);
+
+    assert_eq!(s.treasury.try_execute_change(&id), Err(Ok(TreasuryError::NotReady.into())));
+    let intruder = Address::generate(&s.env);
+    assert_eq!(s.treasury.try_cancel_change(&intruder, &id), Err(Ok(TreasuryError::NotGuardian.into())));
+
+    s.treasury.cancel_change(&guardian, &id);
+    assert_eq!(s.treasury.pending_changes().len(), 0);
+    assert_eq!(s.treasury.try_execute_change(&id), Err(Ok(TreasuryError::ChangeNotFound.into())));
+
+    let id = s.treasury.queue_change(&s.admin, &change);
+    s.env.ledger().set_timestamp(s.env.ledger().timestamp() + s.treasury.min_delay());
+    s.treasury.execute_change(&id);
+    assert_eq!(s.treasury.splits().get(0).unwrap().recipient, new_pools[0]);
+}
+
+#[test]
+fn escrow_and_dust_changes_wait_for_timelock() {
+    let s = setup();
+    let dust = Address::generate(&s.env);
+    let escrow_id = s.treasury.queue_change(&s.admin, &ConfigChange::Escrow(true));
+    let dust_id = s.treasury.queue_change(&s.admin, &ConfigChange::Dust(dust.clone()));
+    assert_eq!(s.treasury.try_execute_change(&escrow_id), Err(Ok(TreasuryError::NotReady.into())));
+    assert_eq!(s.treasury.try_execute_change(&dust_id), Err(Ok(TreasuryError::NotReady.into())));
+
+    // Mientras tanto se sigue ruteando con la configuración vigente
+    route(&s, 1_001);
+    assert!(!s.treasury.escrow());
+    assert_eq!(s.token.balance(&s.pools[2]), 201);
+
+    s.env.ledger().set_timestamp(s.env.ledger().timestamp() + s.treasury.min_delay());
+    s.treasury.execute_change(&escrow_id);
+    s.treasury.execute_change(&dust_id);
+    assert!(s.treasury.escrow());
+    assert_eq!(s.treasury.dust_recipient(), dust);
+}
+
+#[test]
+fn route_requires_registered_source_and_is_idempotent() {
+    let s = setup();
+    let stranger = Address::generate(&s.env);
+    assert_eq!(
+        s.treasury.try_route(&stranger, &s.token.address, &s.from, &100, &1),
+        Err(Ok(TreasuryError::UnknownSource.into()))
+    );
+
+    let first = s.treasury.route(&s.source, &s.token.address, &s.from, &1_000, &7);
+    let again = s.treasury.route(&s.source, &s.token.address, &s.from, &1_000, &7);
+    assert_eq!(first, again);
+    assert_eq!(balances(&s, &[]), 1_000);
+    assert_eq!(s.treasury.get_payment(&s.source, &7).unwrap().shares, first);
+
+    assert_eq!(
+        s.treasury.try_route(&s.source, &s.token.address, &s.from, &999, &7),
+        Err(Ok(TreasuryError::ReferenceMismatch.into()))
+    );
+}
+
+fn bps_table() -> impl Strategy<Value = std::vec::Vec<u32>> {
+    // Entre 1 y 6 cortes aleatorios de [0, 10_000] -> porciones no nulas que suman 10_000
+    proptest::collection::btree_set(1u32..10_000, 0..6).prop_map(|cuts| {
+        let mut bps = std::vec::Vec::new();
+        let mut last = 0;
+        for c in cuts.into_iter().chain(core::iter::once(10_000)) {
+            bps.push(c - last);
+            last = c;
+        }
+        bps
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(200))]
+
+    #[test]
+    fn route_conserves_amount(
+        amount in prop_oneof![0i128..1_000_000, any::<u64>().prop_map(i128::from), 0i128..=i128::MAX / 10_000],
+        bps in bps_table(),
+        dust_in_table in any::<bool>(),
+    ) {
+        let s = setup();
+        let recipients: std::vec::Vec<Address> = bps.iter().map(|_| Address::generate(&s.env)).collect();
+        let mut table = Vec::new(&s.env);
+        for (r, b) in recipients.iter().zip(bps.iter()) {
+            table.push_back(Split { recipient: r.clone(), bps: *b });
+        }
+        apply_change(&s, ConfigChange::Splits(table));
+
+        let dust = if dust_in_table { recipients[0].clone() } else { Address::generate(&s.env) };
+        apply_change(&s, ConfigChange::Dust(dust.clone()));
+
+        let routed = route(&s, amount);
+
+        let mut holders = recipients.clone();
+        if !dust_in_table {
+            holders.push(dust);
+        }
+        let routed_sum: i128 = routed.iter().map(|(_, a)| a).sum();
+        let received: i128 = holders.iter().map(|a| s.token.balance(a)).sum();
+        prop_assert_eq!(routed_sum, amount);
+        prop_assert_eq!(received, amount);
+        prop_assert_eq!(s.token.balance(&s.from), i128::MAX - amount);
+    }
+}
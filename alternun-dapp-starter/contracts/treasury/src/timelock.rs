@@ -0,0 +1,199 @@
+//! Cambios de configuración con timelock.
+//!
+//! Los cambios de pools, tablas de splits, receptor de dust, modo escrow y
+//! firmantes no se aplican en el acto: el admin los encola, quedan visibles en
+//! `pending_changes` y sólo se pueden ejecutar cuando el ledger alcanza su `eta`
+//! (como el `TimeBoundKind::After` del ejemplo `timelock`). Mientras tanto el
+//! guardian (o el propio admin) puede cancelarlos.
+
+use soroban_sdk::{
+    contractimpl, contracttype, panic_with_error, symbol_short,
+    Address, Env, Vec,
+};
+
+use crate::disbursement::{validate_signers, write_signers, PoolSigners};
+use crate::{
+    default_splits, read_token_rule, require_admin, validate_splits, DataKey, State, Split, Treasury,
+    TreasuryClient, TreasuryError,
+};
+
+// Demora por defecto entre encolar y ejecutar (48 h).
+pub(crate) const DEFAULT_DELAY_SECS: u64 = 48 * 60 * 60;
+// Límites de la demora: nunca menos de 24 h ni más de 30 días, así no se puede
+// anular el timelock ni congelar la configuración.
+const MIN_DELAY_SECS: u64 = 24 * 60 * 60;
+const MAX_DELAY_SECS: u64 = 30 * 24 * 60 * 60;
+const MAX_PENDING: u32 = 20;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigChange {
+    Pools(Address, Address, Address),               // (projects, recovery, alternun); resetea splits a 50/30/20
+    Splits(Vec<Split>),                             // tabla global
+    TokenSplits(Address, Option<Vec<Split>>),       // tabla propia de un token aceptado (None = global)
+    Guardian(Address),
+    MinDelay(u64),                                  // segundos, entre 24 h y 30 días
+    Dust(Address),                                  // receptor del residuo de redondeo
+    Escrow(bool),                                   // custodia en vez de push
+    PoolSigners(Address, PoolSigners),              // firmantes de un pool (vacío y umbral 0 = sin multisig)
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingChange {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub eta: u64, // timestamp del ledger desde el que se puede ejecutar
+}
+
+fn read_delay(e: &Env) -> u64 {
+    e.storage().instance().get(&State::DELAY).unwrap_or(DEFAULT_DELAY_SECS)
+}
+
+fn read_guardian(e: &Env) -> Option<Address> {
+    e.storage().instance().get(&State::GUARDIAN)
+}
+
+fn read_pending_ids(e: &Env) -> Vec<u64> {
+    e.storage().instance().get(&State::PENDING).unwrap_or(Vec::new(e))
+}
+
+fn read_change(e: &Env, id: u64) -> PendingChange {
+    match e.storage().persistent().get(&DataKey::Change(id)) {
+        Some(c) => c,
+        None => panic_with_error!(e, TreasuryError::ChangeNotFound),
+    }
+}
+
+// Saca el cambio de la cola pendiente.
+fn remove_change(e: &Env, id: u64) {
+    e.storage().persistent().remove(&DataKey::Change(id));
+    let mut ids = read_pending_ids(e);
+    if let Some(i) = ids.first_index_of(id) {
+        ids.remove(i);
+        e.storage().instance().set(&State::PENDING, &ids);
+    }
+}
+
+// Valida al encolar para no esperar la demora y fallar recién al ejecutar.
+fn validate_change(e: &Env, change: &ConfigChange) {
+    match change {
+        ConfigChange::Splits(splits) => validate_splits(e, splits),
+        ConfigChange::TokenSplits(_, Some(splits)) => validate_splits(e, splits),
+        ConfigChange::PoolSigners(_, cfg) => validate_signers(e, cfg),
+        ConfigChange::MinDelay(secs) if !(MIN_DELAY_SECS..=MAX_DELAY_SECS).contains(secs) => {
+            panic_with_error!(e, TreasuryError::InvalidDelay)
+        }
+        _ => {}
+    }
+}
+
+fn apply_change(e: &Env, change: ConfigChange) {
+    match change {
+        ConfigChange::Pools(addr_p, addr_r, addr_a) => {
+            e.storage().instance().set(&State::ADDR_P, &addr_p);
+            e.storage().instance().set(&State::ADDR_R, &addr_r);
+            e.storage().instance().set(&State::ADDR_A, &addr_a);
+            e.storage().instance().set(&State::SPLITS, &default_splits(e, &addr_p, &addr_r, &addr_a));
+        }
+        ConfigChange::Splits(splits) => {
+            e.storage().instance().set(&State::SPLITS, &splits);
+        }
+        ConfigChange::TokenSplits(token, splits) => {
+            // El token tiene que seguir aceptado al momento de ejecutar
+            let mut rule = read_token_rule(e, &token);
+            rule.splits = splits;
+            e.storage().persistent().set(&DataKey::TokenRule(token), &rule);
+        }
+        ConfigChange::Guardian(guardian) => {
+            e.storage().instance().set(&State::GUARDIAN, &guardian);
+        }
+        ConfigChange::MinDelay(secs) => {
+            e.storage().instance().set(&State::DELAY, &secs);
+        }
+        ConfigChange::Dust(recipient) => {
+            e.storage().instance().set(&State::DUST, &recipient);
+        }
+        ConfigChange::Escrow(enabled) => {
+            e.storage().instance().set(&State::ESCROW, &enabled);
+        }
+        ConfigChange::PoolSigners(pool, cfg) => {
+            write_signers(e, &pool, &cfg);
+        }
+    }
+}
+
+#[contractimpl]
+impl Treasury {
+    /// Encola `change` (solo admin) para ejecutarse tras la demora vigente.
+    /// Devuelve el id.
+    pub fn queue_change(e: Env, admin: Address, change: ConfigChange) -> u64 {
+        require_admin(&e, &admin);
+        validate_change(&e, &change);
+
+        let mut ids = read_pending_ids(&e);
+        if ids.len() >= MAX_PENDING {
+            panic_with_error!(&e, TreasuryError::TooManyPending);
+        }
+
+        let id = e.storage().instance().get::<_, u64>(&DataKey::ChangeSeq).unwrap_or(0) + 1;
+        e.storage().instance().set(&DataKey::ChangeSeq, &id);
+
+        let eta = match e.ledger().timestamp().checked_add(read_delay(&e)) {
+            Some(eta) => eta,
+            None => panic_with_error!(&e, TreasuryError::InvalidDelay),
+        };
+        e.storage().persistent().set(&DataKey::Change(id), &PendingChange { id, change, eta });
+        ids.push_back(id);
+        e.storage().instance().set(&State::PENDING, &ids);
+
+        e.events().publish((symbol_short!("queued"), id), eta);
+        id
+    }
+
+    /// Aplica un cambio encolado cuyo `eta` ya pasó. Lo puede llamar cualquiera.
+    pub fn execute_change(e: Env, id: u64) {
+        let pending = read_change(&e, id);
+        if e.ledger().timestamp() < pending.eta {
+            panic_with_error!(&e, TreasuryError::NotReady);
+        }
+        remove_change(&e, id);
+        apply_change(&e, pending.change);
+        e.events().publish((symbol_short!("executed"), id), ());
+    }
+
+    /// Descarta un cambio pendiente. Pueden hacerlo el guardian o el admin.
+    pub fn cancel_change(e: Env, caller: Address, id: u64) {
+        let admin: Address = e.storage().instance().get(&State::ADMIN).unwrap();
+        if caller != admin && Some(caller.clone()) != read_guardian(&e) {
+            panic_with_error!(&e, TreasuryError::NotGuardian);
+        }
+        caller.require_auth();
+
+        read_change(&e, id);
+        remove_change(&e, id);
+        e.events().publish((symbol_short!("cancelled"), id), caller);
+    }
+
+    /// Cambios encolados y aún no ejecutados ni cancelados, en orden de llegada.
+    pub fn pending_changes(e: Env) -> Vec<PendingChange> {
+        let mut out = Vec::new(&e);
+        for id in read_pending_ids(&e).iter() {
+            out.push_back(read_change(&e, id));
+        }
+        out
+    }
+
+    pub fn get_change(e: Env, id: u64) -> Option<PendingChange> {
+        e.storage().persistent().get(&DataKey::Change(id))
+    }
+
+    pub fn guardian(e: Env) -> Option<Address> {
+        read_guardian(&e)
+    }
+
+    /// Segundos entre encolar y poder ejecutar un cambio.
+    pub fn min_delay(e: Env) -> u64 {
+        read_delay(&e)
+    }
+}
@@ -0,0 +1,21 @@
+#!/usr/bin/env bash
+set -euo pipefail
+echo "Building pgbt_token..."
+cargo build -p pgbt_token --release
+
+WASM=../target/pgbt_token.wasm
+if [ ! -f "$WASM" ]; then
+  WASM=$(find ../target -name "*pgbt_token*.wasm" | head -n1)
+fi
+
+echo "Deploying pgbt_token..."
+CID=$(soroban contract deploy --wasm "$WASM" --network testnet --source alternun-admin)
+echo "CONTRACT_ID_PGBT_TOKEN=$CID"
+
+# `init` no pide firma: se llama en seguida con el vault como admin. Si alguien se
+# adelantara, vault.set_receipt_token rechaza el recibo y hay que redesplegar.
+if [ -n "${CONTRACT_ID_PROJECT_VAULT:-}" ]; then
+  echo "Initializing pgbt_token with the vault as admin..."
+  soroban contract invoke --id "$CID" --network testnet --source alternun-admin -- \
+    init --admin "$CONTRACT_ID_PROJECT_VAULT" --decimal 7 --name pGBT --symbol pGBT
+fi